@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, JsonBody};
+
+// Usulan tugas hasil ekstraksi AI. Tidak ada yang jadi reminder sebelum user konfirmasi.
+const STATUSES: &[&str] = &["proposed", "accepted", "dismissed"];
+
+// Source log yang boleh diekstraksi otomatis: hanya tulisan user sendiri
+const AUTO_EXTRACT_SOURCES: &[&str] = &["Manual"];
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS proposed_actions (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            log_entry_id INTEGER NOT NULL REFERENCES log_entries(id) ON DELETE CASCADE, -- Log asal
+            kind TEXT NOT NULL,                -- 'reminder' (ada tanggal) atau 'task'
+            title TEXT NOT NULL,
+            due_at TEXT,                       -- Waktu lokal YYYY-MM-DD HH:MM:SS, NULL kalau tanpa tanggal
+            source_text TEXT,                  -- Kalimat asli di log
+            status TEXT DEFAULT 'proposed',    -- proposed, accepted, dismissed
+            reminder_id INTEGER REFERENCES reminders(id) ON DELETE SET NULL, -- Reminder hasil konfirmasi
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );"
+    ).execute(pool).await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_proposed_actions_user_status ON proposed_actions(user_id, status)")
+        .execute(pool)
+        .await?;
+
+    // Ekstraksi otomatis = isi log dikirim ke AI cloud, jadi harus dinyalakan sendiri oleh user
+    // (sejalan dengan allow_data_training yang juga default mati)
+    crate::db::add_column_if_missing(pool, "app_settings", "auto_extract_actions", "BOOLEAN DEFAULT FALSE").await?;
+
+    Ok(())
+}
+
+// ==========================================
+// MODEL
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ProposedAction {
+    pub id: i64,
+    pub log_entry_id: i64,
+    pub kind: String,
+    pub title: String,
+    pub due_at: Option<String>,
+    pub source_text: Option<String>,
+    pub status: String,
+    pub reminder_id: Option<i64>,
+    pub created_at: String,
+}
+
+const ACTION_COLUMNS: &str = "id, log_entry_id, kind, title, due_at, source_text,
+    COALESCE(status, 'proposed') as status, reminder_id, CAST(created_at AS TEXT) as created_at";
+
+#[derive(Deserialize)]
+pub struct ActionFilter {
+    pub status: Option<String>,
+    pub log_entry_id: Option<i64>,
+}
+
+// Saat konfirmasi user boleh koreksi judul/waktu hasil tebakan AI (kirim {} kalau tidak ada)
+#[derive(Deserialize)]
+pub struct AcceptRequest {
+    pub title: Option<String>,
+    pub due_at: Option<String>,
+    pub note: Option<String>,
+}
+
+async fn find(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<ProposedAction> {
+    sqlx::query_as::<_, ProposedAction>(&format!("SELECT {} FROM proposed_actions WHERE id = ? AND user_id = ?", ACTION_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Usulan #{} tidak ditemukan", id)))
+}
+
+async fn list_for_entry(pool: &SqlitePool, user_id: i64, log_id: i64) -> Result<Vec<ProposedAction>, sqlx::Error> {
+    sqlx::query_as::<_, ProposedAction>(&format!(
+        "SELECT {} FROM proposed_actions WHERE user_id = ? AND log_entry_id = ? ORDER BY id ASC", ACTION_COLUMNS
+    ))
+    .bind(user_id)
+    .bind(log_id)
+    .fetch_all(pool)
+    .await
+}
+
+// ==========================================
+// EKSTRAKSI
+// ==========================================
+
+// Minta AI membaca log lalu simpan hasilnya sebagai usulan. Usulan lama yang belum
+// dijawab untuk log yang sama diganti, yang sudah diterima/ditolak dibiarkan.
+pub async fn extract_for_entry(pool: &SqlitePool, user_id: i64, log_id: i64) -> AppResult<Vec<ProposedAction>> {
+    let entry = crate::logbook::fetch_log(pool, user_id, log_id).await?
+        .ok_or_else(|| AppError::not_found(format!("Log #{} tidak ditemukan", log_id)))?;
+
+    let ai = crate::ai_features::load_client(pool, user_id).await?;
+    let extracted = crate::ai_features::extract_actions(&ai, &entry.content, &entry.entry_date, &entry.entry_time).await?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM proposed_actions WHERE user_id = ? AND log_entry_id = ? AND status = 'proposed'")
+        .bind(user_id)
+        .bind(log_id)
+        .execute(&mut *tx)
+        .await?;
+
+    for action in extracted {
+        // Tanggal yang gak bisa dibaca dianggap tugas tanpa tanggal, bukan error
+        let due_at = action.due_at.as_deref()
+            .and_then(crate::reminders::parse_datetime)
+            .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string());
+        let kind = if due_at.is_some() { "reminder" } else { "task" };
+
+        sqlx::query(
+            "INSERT INTO proposed_actions (user_id, log_entry_id, kind, title, due_at, source_text) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(log_id)
+        .bind(kind)
+        .bind(action.title.trim())
+        .bind(due_at)
+        .bind(action.source_text.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()))
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    let actions = list_for_entry(pool, user_id, log_id).await?;
+    Ok(actions.into_iter().filter(|a| a.status == "proposed").collect())
+}
+
+// Dipanggil insert_log untuk setiap log baru, tapi hanya log yang ditulis user sendiri
+// (AUTO_EXTRACT_SOURCES). Entry dari integrasi (GitHub, Telegram) & import tidak pernah
+// dikirim ke AI tanpa diminta; usulannya tetap bisa diminta lewat POST /api/logs/{id}/extract.
+// Jalan di background supaya simpan log tetap cepat, dan diam saja kalau AI belum diset
+// atau fitur belum dinyalakan user.
+pub fn spawn_auto_extract(pool: SqlitePool, user_id: i64, log_id: i64, source: &str) {
+    if !AUTO_EXTRACT_SOURCES.iter().any(|s| s.eq_ignore_ascii_case(source)) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let enabled: Option<(Option<bool>, Option<String>)> = match sqlx::query_as(
+            "SELECT auto_extract_actions, ai_api_key FROM app_settings WHERE user_id = ? LIMIT 1"
+        )
+        .bind(user_id)
+        .fetch_optional(&pool)
+        .await
+        {
+            Ok(row) => row,
+            Err(e) => {
+                eprintln!("❌ Gagal membaca settings ekstraksi: {}", e);
+                return;
+            }
+        };
+
+        let ready = enabled.is_some_and(|(auto, key)| auto.unwrap_or(false) && key.is_some_and(|k| !k.is_empty()));
+        if !ready {
+            return;
+        }
+
+        match extract_for_entry(&pool, user_id, log_id).await {
+            Ok(actions) if !actions.is_empty() => {
+                let titles: Vec<&str> = actions.iter().map(|a| a.title.as_str()).collect();
+                let title = format!("📝 {} usulan tugas dari log #{}", actions.len(), log_id);
+                if let Err(e) = crate::reminders::push_notification(&pool, user_id, &title, Some(&titles.join("\n"))).await {
+                    eprintln!("❌ Gagal membuat notifikasi usulan: {}", e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("⚠️ Ekstraksi tugas log #{} gagal: {}", log_id, e.message),
+        }
+    });
+}
+
+// ==========================================
+// API
+// ==========================================
+
+// GET /api/actions?status=proposed&log_entry_id=12
+pub async fn list_actions(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(filter): Query<ActionFilter>,
+) -> AppResult<Json<Vec<ProposedAction>>> {
+    let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!("SELECT {} FROM proposed_actions WHERE user_id = ", ACTION_COLUMNS));
+    qb.push_bind(user.id);
+    if let Some(status) = filter.status {
+        if !STATUSES.contains(&status.as_str()) {
+            return Err(AppError::bad_request(format!("Status harus salah satu dari: {}", STATUSES.join(", "))));
+        }
+        qb.push(" AND status = ").push_bind(status);
+    }
+    if let Some(log_id) = filter.log_entry_id {
+        qb.push(" AND log_entry_id = ").push_bind(log_id);
+    }
+    qb.push(" ORDER BY created_at DESC, id ASC");
+
+    Ok(Json(qb.build_query_as::<ProposedAction>().fetch_all(&pool).await?))
+}
+
+// POST /api/logs/{id}/extract -> jalankan ekstraksi sekarang (manual / ulang)
+pub async fn extract_now(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(log_id): Path<i64>,
+) -> AppResult<(StatusCode, Json<Vec<ProposedAction>>)> {
+    let actions = extract_for_entry(&pool, user.id, log_id).await?;
+    Ok((StatusCode::CREATED, Json(actions)))
+}
+
+// POST /api/actions/{id}/accept -> usulan bertanggal jadi reminder yang tertaut ke log asal,
+// usulan tanpa tanggal cukup ditandai diterima (jadi daftar tugas)
+pub async fn accept_action(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<AcceptRequest>,
+) -> AppResult<Json<ProposedAction>> {
+    let action = find(&pool, user.id, id).await?;
+    if action.status != "proposed" {
+        return Err(AppError::conflict(format!("Usulan #{} sudah {}", id, action.status)).with_code("action_closed"));
+    }
+
+    let title = payload.title.filter(|t| !t.trim().is_empty()).unwrap_or(action.title);
+    let due_at = payload.due_at.filter(|d| !d.trim().is_empty()).or(action.due_at);
+
+    let reminder_id = match due_at {
+        Some(due_at) => {
+            let note = payload.note.or(action.source_text.map(|s| format!("Dari log #{}: \"{}\"", action.log_entry_id, s)));
+            let reminder = crate::reminders::insert_reminder(&pool, user.id, crate::reminders::ReminderRequest {
+                title: title.clone(),
+                note,
+                due_at,
+                log_entry_id: Some(action.log_entry_id),
+                recurrence: None,
+                recurrence_interval: None,
+                recurrence_until: None,
+                status: None,
+            }).await?;
+            Some(reminder.id)
+        }
+        None => None,
+    };
+
+    // Kind ikut disesuaikan kalau user menambahkan tanggal sendiri
+    sqlx::query(
+        "UPDATE proposed_actions SET status = 'accepted', title = ?, reminder_id = ?,
+                kind = CASE WHEN ? IS NULL THEN kind ELSE 'reminder' END,
+                due_at = COALESCE((SELECT due_at FROM reminders WHERE id = ?), due_at)
+         WHERE id = ? AND user_id = ?"
+    )
+    .bind(&title)
+    .bind(reminder_id)
+    .bind(reminder_id)
+    .bind(reminder_id)
+    .bind(id)
+    .bind(user.id)
+    .execute(&pool)
+    .await?;
+
+    Ok(Json(find(&pool, user.id, id).await?))
+}
+
+// POST /api/actions/{id}/dismiss
+pub async fn dismiss_action(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<ProposedAction>> {
+    let action = find(&pool, user.id, id).await?;
+    if action.status != "proposed" {
+        return Err(AppError::conflict(format!("Usulan #{} sudah {}", id, action.status)).with_code("action_closed"));
+    }
+
+    sqlx::query("UPDATE proposed_actions SET status = 'dismissed' WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+
+    Ok(Json(find(&pool, user.id, id).await?))
+}
@@ -1,11 +1,14 @@
 use axum::{
     extract::State,
     response::Json,
-    Json as JsonBody,
+    Extension,
 };
 use sqlx::SqlitePool;
 use serde::{Deserialize, Serialize};
 
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, JsonBody};
+
 #[derive(Deserialize)]
 pub struct PolishRequest {
     pub draft_content: String,
@@ -21,6 +24,15 @@ pub struct PolishResponse {
 #[derive(Serialize, Deserialize, Debug)] // <--- FIX: Tambah Deserialize
 struct GeminiRequest {
     contents: Vec<GeminiContent>,
+    #[serde(rename = "generationConfig", skip_serializing_if = "Option::is_none")]
+    generation_config: Option<GenerationConfig>,
+}
+
+// Minta jawaban JSON murni (dipakai ekstraksi tugas)
+#[derive(Serialize, Deserialize, Debug)]
+struct GenerationConfig {
+    #[serde(rename = "responseMimeType")]
+    response_mime_type: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)] // <--- FIX: Tambah Deserialize
@@ -44,41 +56,86 @@ struct GeminiCandidate {
     content: GeminiContent,
 }
 
-// --- HANDLER ---
+// --- KLIEN AI (API key, model & persona aktif milik user) ---
 
-pub async fn polish_content(
-    State(pool): State<SqlitePool>,
-    JsonBody(payload): JsonBody<PolishRequest>,
-) -> Json<PolishResponse> {
-    
+pub struct AiClient {
+    api_key: String,
+    model: String,
+    persona_name: String,
+    persona_prompt: String,
+}
+
+pub async fn load_client(pool: &SqlitePool, user_id: i64) -> AppResult<AiClient> {
     // 1. Ambil API Key & Active Persona dari DB
-    let settings = sqlx::query!("SELECT ai_api_key, ai_model_name FROM app_settings LIMIT 1")
-        .fetch_optional(&pool).await.unwrap_or(None);
+    let settings: Option<(Option<String>, Option<String>)> = sqlx::query_as("SELECT ai_api_key, ai_model_name FROM app_settings WHERE user_id = ? LIMIT 1")
+        .bind(user_id)
+        .fetch_optional(pool).await?;
 
-    let active_persona = sqlx::query!("SELECT name, system_prompt FROM ai_personas WHERE is_active = TRUE LIMIT 1")
-        .fetch_optional(&pool).await.unwrap_or(None);
+    let active_persona: Option<(String, String)> = sqlx::query_as("SELECT name, system_prompt FROM ai_personas WHERE is_active = TRUE AND user_id = ? LIMIT 1")
+        .bind(user_id)
+        .fetch_optional(pool).await?;
 
     // Default values
-// Default values
-    let api_key = settings.as_ref().and_then(|s| s.ai_api_key.clone()).unwrap_or_default();
+    let api_key = crate::crypto::reveal(settings.as_ref().and_then(|s| s.0.clone())).unwrap_or_default();
     
 // --- FIX SAFETY NET ---
     // Ambil model dari DB. Kalau kosong atau None, paksa pakai 'gemini-3-flash-preview'
     let model = settings.as_ref()
-        .and_then(|s| s.ai_model_name.clone())
+        .and_then(|s| s.1.clone())
         .filter(|s| !s.is_empty()) // Filter: kalau string kosong, jadikan None
         .unwrap_or("gemini-3-flash-preview".to_string()); // Fallback aman
     
     // Default Persona: Scribe
     let (persona_name, persona_prompt) = match active_persona {
-        Some(p) => (p.name, p.system_prompt),
+        Some(p) => p,
         None => ("Scribe".to_string(), "You are a professional editor. Rewrite the text clearly.".to_string())
     };
 
     if api_key.is_empty() {
-        return Json(PolishResponse { polished_content: "⚠️ API Key belum diset di Settings.".to_string() });
+        return Err(AppError::bad_request("API Key belum diset di Settings.").with_code("ai_not_configured"));
     }
 
+    Ok(AiClient { api_key, model, persona_name, persona_prompt })
+}
+
+impl AiClient {
+    // Tembak Gemini, balikin teks jawaban pertama
+    async fn generate(&self, prompt: String, json_output: bool) -> AppResult<String> {
+        let client = reqwest::Client::new();
+        let gemini_payload = GeminiRequest {
+            contents: vec![GeminiContent { role: "user".to_string(), parts: vec![GeminiPart { text: prompt }] }],
+            generation_config: json_output.then(|| GenerationConfig { response_mime_type: "application/json".to_string() }),
+        };
+
+        let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}", self.model, self.api_key);
+        
+        // without_url: URL berisi API key
+        let response = client.post(&url).json(&gemini_payload).send().await
+            .map_err(|e| AppError::upstream(format!("Gagal menghubungi AI: {}", e.without_url())).with_code("ai_unreachable"))?;
+
+        if !response.status().is_success() {
+            return Err(AppError::upstream(format!("AI menolak permintaan ({})", response.status())).with_code("ai_error"));
+        }
+
+        let data: GeminiResponse = response.json().await
+            .map_err(|e| AppError::upstream(format!("Jawaban AI tidak bisa dibaca: {}", e.without_url())).with_code("ai_error"))?;
+        data.candidates
+            .and_then(|c| c.into_iter().next())
+            .and_then(|c| c.content.parts.into_iter().next())
+            .map(|p| p.text)
+            .ok_or(AppError::upstream("AI tidak mengembalikan teks").with_code("ai_error"))
+    }
+}
+
+// --- HANDLER ---
+
+pub async fn polish_content(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<PolishRequest>,
+) -> AppResult<Json<PolishResponse>> {
+    let ai = load_client(&pool, user.id).await?;
+
 // 2. Susun Prompt (Versi Bebas Format)
     let system_instruction = format!(
         r#"
@@ -99,33 +156,65 @@ pub async fn polish_content(
         Draft to Rewrite:
         "{}"
         "#,
-        persona_name,
-        persona_prompt,
+        ai.persona_name,
+        ai.persona_prompt,
         payload.draft_content
     );
     // 3. Tembak Gemini
-    let client = reqwest::Client::new();
-    let gemini_payload = GeminiRequest {
-        contents: vec![GeminiContent { role: "user".to_string(), parts: vec![GeminiPart { text: system_instruction }] }],
-    };
+    let result_text = ai.generate(system_instruction, false).await?;
 
-    let url = format!("https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}", model, api_key);
-    
-    let res = client.post(&url).json(&gemini_payload).send().await;
-
-    let result_text = match res {
-        Ok(response) => {
-            if response.status().is_success() {
-                let data: GeminiResponse = response.json().await.unwrap_or(GeminiResponse { candidates: None });
-                data.candidates.and_then(|c| c.into_iter().next())
-                    .map(|c| c.content.parts[0].text.clone())
-                    .unwrap_or(payload.draft_content) // Kalau gagal parse, balikin original
-            } else {
-                format!("Error AI: {}", response.status())
-            }
-        },
-        Err(_) => "Error Connection".to_string(),
-    };
+    Ok(Json(PolishResponse { polished_content: result_text }))
+}
+
+// --- EKSTRAKSI TUGAS & TANGGAL DARI LOG ---
+
+#[derive(Deserialize, Debug)]
+pub struct ExtractedAction {
+    pub title: String,
+    pub due_at: Option<String>,      // "YYYY-MM-DD HH:MM" atau null kalau gak ada tanggal
+    pub source_text: Option<String>, // Potongan kalimat asli di log
+}
+
+// Cari action item ("kirim laporan sebelum Jumat") di isi log. Tanggal relatif dihitung
+// dari tanggal log itu sendiri, bukan hari ini, supaya log backdate tetap benar.
+pub async fn extract_actions(ai: &AiClient, content: &str, entry_date: &str, entry_time: &str) -> AppResult<Vec<ExtractedAction>> {
+    let weekday = chrono::NaiveDate::parse_from_str(entry_date, "%Y-%m-%d")
+        .map(|d| d.format("%A").to_string())
+        .unwrap_or_default();
 
-    Json(PolishResponse { polished_content: result_text })
-}
\ No newline at end of file
+    let prompt = format!(
+        r#"
+        ROLE: You are Noty, utilizing the '{}' persona.
+        CORE INSTRUCTION: {}
+
+        TASK:
+        Find concrete action items, deadlines and appointments the author commits to in the logbook entry below.
+        Ignore things that are already done, vague wishes, and things other people must do.
+
+        The entry was written on {} ({}) at {}. Resolve relative dates ("besok", "Jumat", "next week") against that moment.
+
+        Answer with a JSON array only, no prose. Each item:
+        {{"title": short imperative task in the entry's language,
+          "due_at": "YYYY-MM-DD HH:MM" or null when no date is mentioned (use 09:00 when only a day is given),
+          "source_text": the exact phrase from the entry}}
+        Answer [] when there is nothing to do.
+
+        Entry:
+        "{}"
+        "#,
+        ai.persona_name,
+        ai.persona_prompt,
+        entry_date,
+        weekday,
+        entry_time,
+        content
+    );
+
+    let raw = ai.generate(prompt, true).await?;
+    // Jaga-jaga kalau model tetap membungkus jawaban dengan ```json
+    let cleaned = raw.trim().trim_start_matches("```json").trim_start_matches("```").trim_end_matches("```").trim();
+    let actions: Vec<ExtractedAction> = serde_json::from_str(cleaned)
+        .map_err(|e| AppError::upstream(format!("Jawaban AI bukan daftar tugas yang valid: {}", e)).with_code("ai_error"))?;
+
+    Ok(actions.into_iter().filter(|a| !a.title.trim().is_empty()).collect())
+}
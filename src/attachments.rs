@@ -0,0 +1,383 @@
+use axum::{
+    extract::{multipart::MultipartRejection, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use crate::auth::AuthUser;
+use crate::config::AttachmentsConfig;
+use crate::error::{AppError, AppResult};
+
+// Layout di disk (content-addressed, file yang sama cuma disimpan sekali):
+//   {dir}/objects/ab/ab12cd...      isi file asli
+//   {dir}/thumbs/ab/ab12cd....jpg   thumbnail untuk gambar
+const THUMB_SIZE: u32 = 320;
+const THUMB_QUALITY: u8 = 80;
+// Gambar raksasa gak usah dibuatkan thumbnail (cegah decompression bomb)
+const MAX_IMAGE_SIDE: u32 = 12_000;
+
+// File yang baru ditulis/dipakai ulang belum tentu sudah tercatat di tabel, jangan ikut dibersihkan
+const ORPHAN_GRACE: Duration = Duration::from_secs(60 * 60);
+const CLEANUP_EVERY: Duration = Duration::from_secs(24 * 60 * 60);
+
+// Hanya tipe ini yang boleh tampil inline di browser; sisanya (HTML, SVG, dll) dipaksa download
+const INLINE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp", "application/pdf"];
+
+static CONFIG: OnceLock<AttachmentsConfig> = OnceLock::new();
+
+// Dipanggil sekali di main setelah config dibaca
+pub fn init(config: AttachmentsConfig) {
+    let _ = CONFIG.set(config);
+}
+
+fn config() -> &'static AttachmentsConfig {
+    CONFIG.get_or_init(AttachmentsConfig::default)
+}
+
+pub fn max_upload_bytes() -> usize {
+    // Sedikit kelonggaran untuk header multipart
+    config().max_bytes() + 64 * 1024
+}
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS attachments (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            log_entry_id INTEGER NOT NULL REFERENCES log_entries(id) ON DELETE CASCADE,
+            file_name TEXT NOT NULL,       -- Nama asli dari user (cuma untuk ditampilkan)
+            mime_type TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            sha256 TEXT NOT NULL,          -- Lokasi file di disk
+            has_thumbnail BOOLEAN DEFAULT FALSE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );"
+    ).execute(pool).await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_attachments_entry ON attachments (log_entry_id)")
+        .execute(pool)
+        .await?;
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_attachments_sha256 ON attachments (sha256)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Attachment {
+    pub id: i64,
+    pub log_entry_id: i64,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub has_thumbnail: bool,
+    pub created_at: String,
+}
+
+const ATTACHMENT_COLUMNS: &str = "id, log_entry_id, file_name, mime_type, size_bytes, sha256, COALESCE(has_thumbnail, FALSE) as has_thumbnail, CAST(created_at AS TEXT) as created_at";
+
+// Semua lampiran milik satu entry (dipakai juga oleh detail log)
+pub async fn list_for_entry(pool: &SqlitePool, user_id: i64, log_id: i64) -> Result<Vec<Attachment>, sqlx::Error> {
+    sqlx::query_as::<_, Attachment>(&format!(
+        "SELECT {} FROM attachments WHERE log_entry_id = ? AND user_id = ? ORDER BY id ASC", ATTACHMENT_COLUMNS
+    ))
+    .bind(log_id)
+    .bind(user_id)
+    .fetch_all(pool)
+    .await
+}
+
+async fn find(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<Attachment> {
+    sqlx::query_as::<_, Attachment>(&format!("SELECT {} FROM attachments WHERE id = ? AND user_id = ?", ATTACHMENT_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Lampiran #{} tidak ditemukan", id)))
+}
+
+// ==========================================
+// PENYIMPANAN DI DISK
+// ==========================================
+
+fn object_path(hash: &str) -> PathBuf {
+    config().dir.join("objects").join(&hash[..2]).join(hash)
+}
+
+fn thumb_path(hash: &str) -> PathBuf {
+    config().dir.join("thumbs").join(&hash[..2]).join(format!("{}.jpg", hash))
+}
+
+// Tulis ke file sementara lalu rename, jadi gak ada file setengah jadi kalau proses mati
+async fn write_once(path: &std::path::Path, data: &[u8]) -> std::io::Result<()> {
+    match touch(path).await {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        done => return done,
+    }
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let staged = path.with_extension("tmp");
+    tokio::fs::write(&staged, data).await?;
+    tokio::fs::rename(&staged, path).await
+}
+
+// Isi yang sama sudah ada: segarkan mtime-nya, supaya ORPHAN_GRACE juga melindungi upload ulang
+// yang barisnya belum masuk tabel
+async fn touch(path: &std::path::Path) -> std::io::Result<()> {
+    let file = tokio::fs::OpenOptions::new().write(true).open(path).await?;
+    file.into_std().await.set_modified(SystemTime::now())
+}
+
+// Tipe file ditentukan dari isinya, nama file cuma cadangan
+fn detect_mime(data: &[u8], file_name: &str) -> String {
+    if let Ok(format) = image::guess_format(data) {
+        return format.to_mime_type().to_string();
+    }
+    if data.starts_with(b"%PDF-") {
+        return "application/pdf".to_string();
+    }
+    mime_guess::from_path(file_name).first_or_octet_stream().to_string()
+}
+
+fn clean_file_name(raw: &str) -> String {
+    // Browser lama kirim path lengkap ("C:\\Users\\...\\struk.jpg")
+    let name = raw.rsplit(['/', '\\']).next().unwrap_or("").trim();
+    let name: String = name.chars().filter(|c| !c.is_control()).take(200).collect();
+    if name.is_empty() { "lampiran".to_string() } else { name }
+}
+
+fn make_thumbnail(data: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = image::ImageReader::new(std::io::Cursor::new(data)).with_guessed_format().ok()?;
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_SIDE);
+    limits.max_image_height = Some(MAX_IMAGE_SIDE);
+    reader.limits(limits);
+
+    let thumb = reader.decode().ok()?.thumbnail(THUMB_SIZE, THUMB_SIZE).to_rgb8();
+    let mut out = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, THUMB_QUALITY)
+        .encode_image(&thumb)
+        .ok()?;
+    Some(out)
+}
+
+// ==========================================
+// HANDLERS
+// ==========================================
+
+// POST /api/logs/{id}/attachments (multipart, boleh beberapa file sekaligus)
+pub async fn upload(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(log_id): Path<i64>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> AppResult<(StatusCode, Json<Vec<Attachment>>)> {
+    let mut multipart = multipart.map_err(|e| AppError::new(e.status(), "invalid_upload", e.body_text()))?;
+
+    if crate::logbook::fetch_log(&pool, user.id, log_id).await?.is_none() {
+        return Err(AppError::not_found(format!("Log #{} tidak ditemukan", log_id)));
+    }
+
+    let upload_err = |e: axum::extract::multipart::MultipartError| {
+        AppError::new(e.status(), "invalid_upload", format!("Upload gagal dibaca: {}", e.body_text()))
+    };
+
+    let mut created = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(upload_err)? {
+        let Some(raw_name) = field.file_name().map(str::to_string) else { continue };
+        let data = field.bytes().await.map_err(upload_err)?;
+        if data.is_empty() {
+            return Err(AppError::bad_request(format!("File '{}' kosong", raw_name)).with_code("invalid_upload"));
+        }
+
+        let file_name = clean_file_name(&raw_name);
+        let mime_type = detect_mime(&data, &file_name);
+        let hash = format!("{:x}", Sha256::digest(&data));
+
+        write_once(&object_path(&hash), &data).await
+            .map_err(|e| AppError::internal("Gagal menyimpan lampiran", e))?;
+
+        // Decode gambar berat buat CPU, jangan di thread async
+        let has_thumbnail = if mime_type.starts_with("image/") {
+            let path = thumb_path(&hash);
+            if touch(&path).await.is_ok() {
+                true
+            } else {
+                let bytes = data.clone();
+                match tokio::task::spawn_blocking(move || make_thumbnail(&bytes)).await.ok().flatten() {
+                    Some(thumb) => write_once(&path, &thumb).await.is_ok(),
+                    None => false,
+                }
+            }
+        } else {
+            false
+        };
+
+        let id = sqlx::query(
+            "INSERT INTO attachments (user_id, log_entry_id, file_name, mime_type, size_bytes, sha256, has_thumbnail)
+             VALUES (?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(user.id)
+        .bind(log_id)
+        .bind(&file_name)
+        .bind(&mime_type)
+        .bind(data.len() as i64)
+        .bind(&hash)
+        .bind(has_thumbnail)
+        .execute(&pool)
+        .await?
+        .last_insert_rowid();
+
+        created.push(find(&pool, user.id, id).await?);
+    }
+
+    if created.is_empty() {
+        return Err(AppError::bad_request("Tidak ada file yang di-upload").with_code("invalid_upload"));
+    }
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+// GET /api/logs/{id}/attachments
+pub async fn list(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(log_id): Path<i64>,
+) -> AppResult<Json<Vec<Attachment>>> {
+    if crate::logbook::fetch_log(&pool, user.id, log_id).await?.is_none() {
+        return Err(AppError::not_found(format!("Log #{} tidak ditemukan", log_id)));
+    }
+    Ok(Json(list_for_entry(&pool, user.id, log_id).await?))
+}
+
+// Nama file di header: versi ASCII untuk browser lama + versi UTF-8 (RFC 5987)
+fn content_disposition(attachment: &Attachment) -> String {
+    let kind = if INLINE_TYPES.contains(&attachment.mime_type.as_str()) { "inline" } else { "attachment" };
+    let ascii: String = attachment.file_name.chars()
+        .map(|c| if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' { c } else { '_' })
+        .collect();
+    let encoded: String = attachment.file_name.bytes()
+        .map(|b| if b.is_ascii_alphanumeric() || b"-._~".contains(&b) { (b as char).to_string() } else { format!("%{:02X}", b) })
+        .collect();
+    format!("{}; filename=\"{}\"; filename*=UTF-8''{}", kind, ascii, encoded)
+}
+
+fn file_response(body: Vec<u8>, mime_type: String, disposition: Option<String>) -> Response {
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, mime_type),
+            // Isi file gak pernah berubah untuk hash yang sama
+            (header::CACHE_CONTROL, "private, max-age=31536000, immutable".to_string()),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            // File upload user gak boleh jalan sebagai halaman (HTML/SVG berisi script)
+            (header::CONTENT_SECURITY_POLICY, "sandbox".to_string()),
+        ],
+        body,
+    ).into_response();
+    if let Some(value) = disposition.and_then(|d| d.parse().ok()) {
+        response.headers_mut().insert(header::CONTENT_DISPOSITION, value);
+    }
+    response
+}
+
+// GET /api/attachments/{id}
+pub async fn download(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Response> {
+    let attachment = find(&pool, user.id, id).await?;
+    let body = tokio::fs::read(object_path(&attachment.sha256)).await
+        .map_err(|e| AppError::internal(&format!("File lampiran #{} hilang", id), e))?;
+
+    let disposition = content_disposition(&attachment);
+    Ok(file_response(body, attachment.mime_type, Some(disposition)))
+}
+
+// GET /api/attachments/{id}/thumbnail
+pub async fn thumbnail(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Response> {
+    let attachment = find(&pool, user.id, id).await?;
+    if !attachment.has_thumbnail {
+        return Err(AppError::not_found(format!("Lampiran #{} tidak punya thumbnail", id)));
+    }
+    let body = tokio::fs::read(thumb_path(&attachment.sha256)).await
+        .map_err(|e| AppError::internal(&format!("Thumbnail lampiran #{} hilang", id), e))?;
+
+    Ok(file_response(body, "image/jpeg".to_string(), None))
+}
+
+// DELETE /api/attachments/{id}
+pub async fn delete(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<String>> {
+    let attachment = find(&pool, user.id, id).await?;
+    sqlx::query("DELETE FROM attachments WHERE id = ?").bind(id).execute(&pool).await?;
+
+    // File di disk tidak dihapus di sini: bisa saja ada upload isi yang sama yang sedang berjalan.
+    // Kalau benar sudah tidak dipakai, cleanup_orphans yang membersihkan setelah ORPHAN_GRACE.
+
+    Ok(Json(format!("Lampiran {} dihapus", attachment.file_name)))
+}
+
+// ==========================================
+// PEMBERSIHAN FILE YATIM
+// ==========================================
+
+// Hapus file di disk yang tidak dirujuk tabel attachments lagi
+// (lampiran dihapus, trash dikosongkan, atau upload yang gagal di tengah jalan)
+pub async fn cleanup_orphans(pool: &SqlitePool) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let used: HashSet<String> = sqlx::query_scalar("SELECT DISTINCT sha256 FROM attachments")
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+    let mut removed = 0;
+    for (folder, strip) in [("objects", ""), ("thumbs", ".jpg")] {
+        let root = config().dir.join(folder);
+        let Ok(mut shards) = tokio::fs::read_dir(&root).await else { continue };
+        while let Some(shard) = shards.next_entry().await? {
+            let Ok(mut files) = tokio::fs::read_dir(shard.path()).await else { continue };
+            while let Some(file) = files.next_entry().await? {
+                let name = file.file_name().to_string_lossy().to_string();
+                let hash = name.strip_suffix(strip).unwrap_or(&name);
+                if used.contains(hash) {
+                    continue;
+                }
+                let fresh = file.metadata().await?.modified()?.elapsed().map(|age| age < ORPHAN_GRACE).unwrap_or(true);
+                if !fresh {
+                    tokio::fs::remove_file(file.path()).await?;
+                    removed += 1;
+                }
+            }
+        }
+    }
+    Ok(removed)
+}
+
+pub async fn start_cleanup(pool: SqlitePool) {
+    loop {
+        match cleanup_orphans(&pool).await {
+            Ok(0) => {}
+            Ok(removed) => println!("🧹 {} file lampiran yatim dihapus.", removed),
+            Err(e) => eprintln!("❌ Pembersihan lampiran gagal: {}", e),
+        }
+        tokio::time::sleep(CLEANUP_EVERY).await;
+    }
+}
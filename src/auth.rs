@@ -0,0 +1,581 @@
+use argon2::{
+    password_hash::{rand_core::{OsRng, RngCore}, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    Extension,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD as B64URL, Engine};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::error::{AppError, AppResult, JsonBody};
+
+const SESSION_COOKIE: &str = "noty_session";
+const SESSION_DAYS: i64 = 30;
+const TOKEN_PREFIX: &str = "noty_";
+const MIN_PASSWORD_LEN: usize = 8;
+
+// Login gagal beruntun per username: lewat batas ini ditolak dulu sampai jendela waktunya lewat
+const LOGIN_MAX_FAILURES: u32 = 5;
+const LOGIN_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+static LOGIN_FAILURES: OnceLock<Mutex<HashMap<String, (u32, Instant)>>> = OnceLock::new();
+static DUMMY_HASH: OnceLock<String> = OnceLock::new();
+static SECURE_COOKIE: OnceLock<bool> = OnceLock::new();
+
+// Dipanggil sekali di main setelah config dibaca. Kalau server jalan di HTTPS, cookie
+// session diberi atribut Secure supaya gak pernah terkirim lewat HTTP biasa.
+pub fn init(tls: bool) {
+    let _ = SECURE_COOKIE.set(tls);
+}
+
+fn cookie(value: &str, max_age: i64) -> String {
+    let secure = if *SECURE_COOKIE.get_or_init(|| false) { "; Secure" } else { "" };
+    format!("{}={}; HttpOnly; SameSite=Lax; Path=/; Max-Age={}{}", SESSION_COOKIE, value, max_age, secure)
+}
+
+// Scope token: admin > write > read
+pub const SCOPES: &[&str] = &["read", "write", "admin"];
+
+// Route yang isinya konfigurasi/secret, wajib scope admin
+const ADMIN_PATHS: &[&str] = &["/api/settings", "/api/integrations", "/api/webhooks", "/api/auth/tokens", "/api/auth/password", "/api/users", "/api/admin"];
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY,
+            username TEXT NOT NULL UNIQUE,
+            password_hash TEXT NOT NULL,   -- Argon2 (format PHC)
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );"
+    ).execute(pool).await?;
+
+    // Session cookie untuk UI. Yang disimpan cuma hash token-nya.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            token_hash TEXT NOT NULL UNIQUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            expires_at DATETIME NOT NULL
+        );"
+    ).execute(pool).await?;
+
+    // Personal API token untuk script & bot
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS api_tokens (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            name TEXT NOT NULL,
+            token_prefix TEXT NOT NULL,    -- Beberapa karakter awal, biar bisa dikenali di UI
+            token_hash TEXT NOT NULL UNIQUE,
+            scopes TEXT NOT NULL,          -- JSON array: ['read', 'write']
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            last_used_at DATETIME,
+            revoked BOOLEAN DEFAULT FALSE
+        );"
+    ).execute(pool).await?;
+
+    // Bersihkan session kadaluarsa
+    sqlx::query("DELETE FROM sessions WHERE expires_at < CURRENT_TIMESTAMP").execute(pool).await?;
+
+    Ok(())
+}
+
+// ==========================================
+// HELPER KRIPTO
+// ==========================================
+
+pub fn hash_password(password: &str) -> AppResult<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|h| h.to_string())
+        .map_err(|e| AppError::internal("Gagal hash password", e))
+}
+
+// User placeholder hasil migrasi punya password_hash kosong, jadi gak pernah lolos di sini
+fn verify_password(password: &str, hash: &str) -> bool {
+    PasswordHash::new(hash)
+        .map(|parsed| Argon2::default().verify_password(password.as_bytes(), &parsed).is_ok())
+        .unwrap_or(false)
+}
+
+// Hash pengganti saat username tidak ada, supaya waktu respon login sama dengan password salah
+fn dummy_hash() -> &'static str {
+    DUMMY_HASH.get_or_init(|| hash_password(&random_token()).unwrap_or_default())
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    B64URL.encode(bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+// ==========================================
+// IDENTITAS REQUEST
+// ==========================================
+
+// Dimasukkan middleware ke extensions, handler bisa ambil via Extension<AuthUser>
+#[derive(Clone)]
+pub struct AuthUser {
+    pub id: i64,
+    pub username: String,
+    pub is_admin: bool, // Boleh kelola user lain
+    pub scopes: Vec<String>,
+}
+
+impl AuthUser {
+    pub fn has_scope(&self, needed: &str) -> bool {
+        let rank = |s: &str| SCOPES.iter().position(|x| *x == s);
+        let needed = rank(needed);
+        self.scopes.iter().any(|s| rank(s) >= needed)
+    }
+}
+
+fn session_cookie(headers: &HeaderMap) -> Option<String> {
+    headers.get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<String> {
+    headers.get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|t| t.trim().to_string())
+}
+
+// Cek Bearer token dulu (script/bot), lalu cookie session (UI)
+pub async fn authenticate(pool: &SqlitePool, headers: &HeaderMap) -> Option<AuthUser> {
+    if let Some(token) = bearer_token(headers) {
+        let row: Option<(i64, i64, String, bool, String)> = sqlx::query_as(
+            "SELECT t.id, u.id, u.username, COALESCE(u.is_admin, FALSE), t.scopes FROM api_tokens t JOIN users u ON u.id = t.user_id
+             WHERE t.token_hash = ? AND t.revoked = FALSE"
+        )
+        .bind(hash_token(&token))
+        .fetch_optional(pool)
+        .await
+        .ok()?;
+
+        let (token_id, id, username, is_admin, scopes) = row?;
+        let _ = sqlx::query("UPDATE api_tokens SET last_used_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(token_id)
+            .execute(pool)
+            .await;
+
+        return Some(AuthUser { id, username, is_admin, scopes: serde_json::from_str(&scopes).unwrap_or_default() });
+    }
+
+    let session = session_cookie(headers)?;
+    let (id, username, is_admin): (i64, String, bool) = sqlx::query_as(
+        "SELECT u.id, u.username, COALESCE(u.is_admin, FALSE) FROM sessions s JOIN users u ON u.id = s.user_id
+         WHERE s.token_hash = ? AND s.expires_at > CURRENT_TIMESTAMP"
+    )
+    .bind(hash_token(&session))
+    .fetch_optional(pool)
+    .await
+    .ok()??;
+
+    // Login lewat UI = akses penuh
+    Some(AuthUser { id, username, is_admin, scopes: SCOPES.iter().map(|s| s.to_string()).collect() })
+}
+
+fn required_scope(method: &Method, path: &str) -> &'static str {
+    if ADMIN_PATHS.iter().any(|p| path.starts_with(p)) {
+        "admin"
+    } else if method == Method::GET {
+        "read"
+    } else {
+        "write"
+    }
+}
+
+// Middleware untuk semua route /api (kecuali /api/auth/login, setup, status)
+pub async fn require_auth(
+    State(pool): State<SqlitePool>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(user) = authenticate(&pool, req.headers()).await else {
+        return AppError::unauthorized("Silakan login dulu").into_response();
+    };
+
+    let needed = required_scope(req.method(), req.uri().path());
+    if !user.has_scope(needed) {
+        return AppError::forbidden(format!("Token tidak punya scope '{}'", needed)).into_response();
+    }
+
+    req.extensions_mut().insert(user);
+    next.run(req).await
+}
+
+// ==========================================
+// HANDLER LOGIN / SESSION
+// ==========================================
+
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Serialize)]
+pub struct AuthStatus {
+    pub setup_required: bool, // Belum ada user sama sekali
+    pub authenticated: bool,
+    pub username: Option<String>,
+}
+
+async fn start_session(pool: &SqlitePool, user_id: i64) -> AppResult<HeaderValue> {
+    let token = random_token();
+    sqlx::query("INSERT INTO sessions (user_id, token_hash, expires_at) VALUES (?, ?, datetime('now', ?))")
+        .bind(user_id)
+        .bind(hash_token(&token))
+        .bind(format!("+{} days", SESSION_DAYS))
+        .execute(pool)
+        .await?;
+
+    let cookie = cookie(&token, SESSION_DAYS * 24 * 60 * 60);
+    HeaderValue::from_str(&cookie).map_err(|e| AppError::internal("Gagal membuat cookie", e))
+}
+
+// Sudah ada user yang bisa login? (placeholder hasil migrasi gak dihitung)
+async fn has_account(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE password_hash != '')").fetch_one(pool).await
+}
+
+// Validasi username & password baru, balikin username yang sudah di-trim
+pub fn check_credentials(username: &str, password: &str) -> AppResult<String> {
+    let username = username.trim();
+    if username.is_empty() {
+        return Err(AppError::bad_request("Username wajib diisi"));
+    }
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(AppError::bad_request(format!("Password minimal {} karakter", MIN_PASSWORD_LEN)));
+    }
+    Ok(username.to_string())
+}
+
+// GET status login (public, dipakai UI untuk pilih tampilan login/setup)
+pub async fn auth_status(
+    State(pool): State<SqlitePool>,
+    headers: HeaderMap,
+) -> AppResult<Json<AuthStatus>> {
+    let setup_required = !has_account(&pool).await?;
+    let user = authenticate(&pool, &headers).await;
+
+    Ok(Json(AuthStatus {
+        setup_required,
+        authenticated: user.is_some(),
+        username: user.map(|u| u.username),
+    }))
+}
+
+// SETUP: bikin akun admin pertama. Hanya bisa sekali, selama belum ada akun.
+// Kalau ada user placeholder hasil migrasi, akun itu yang diklaim (data lama ikut).
+pub async fn setup(
+    State(pool): State<SqlitePool>,
+    JsonBody(payload): JsonBody<LoginRequest>,
+) -> AppResult<impl IntoResponse> {
+    if has_account(&pool).await? {
+        return Err(AppError::conflict("Akun sudah dibuat, silakan login"));
+    }
+
+    let username = check_credentials(&payload.username, &payload.password)?;
+    let hash = hash_password(&payload.password)?;
+
+    let placeholder: Option<i64> = sqlx::query_scalar("SELECT id FROM users WHERE password_hash = '' ORDER BY id ASC LIMIT 1")
+        .fetch_optional(&pool)
+        .await?;
+
+    let user_id = match placeholder {
+        Some(id) => {
+            sqlx::query("UPDATE users SET username = ?, password_hash = ?, is_admin = TRUE WHERE id = ?")
+                .bind(&username)
+                .bind(hash)
+                .bind(id)
+                .execute(&pool)
+                .await?;
+            id
+        }
+        None => sqlx::query("INSERT INTO users (username, password_hash, is_admin) VALUES (?, ?, TRUE)")
+            .bind(&username)
+            .bind(hash)
+            .execute(&pool)
+            .await?
+            .last_insert_rowid(),
+    };
+
+    crate::users::ensure_defaults(&pool, user_id).await?;
+
+    let cookie = start_session(&pool, user_id).await?;
+    Ok(([(header::SET_COOKIE, cookie)], Json("Akun dibuat, selamat datang!".to_string())))
+}
+
+// LOGIN
+pub async fn login(
+    State(pool): State<SqlitePool>,
+    JsonBody(payload): JsonBody<LoginRequest>,
+) -> AppResult<impl IntoResponse> {
+    let username = payload.username.trim();
+    if login_blocked(username) {
+        return Err(AppError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "too_many_attempts",
+            format!("Terlalu banyak login gagal, coba lagi dalam {} menit", LOGIN_WINDOW.as_secs() / 60),
+        ));
+    }
+
+    let user: Option<(i64, String)> = sqlx::query_as("SELECT id, password_hash FROM users WHERE username = ?")
+        .bind(username)
+        .fetch_optional(&pool)
+        .await?;
+
+    // Argon2 selalu dijalankan (pakai hash dummy kalau user tidak ada / placeholder),
+    // dan pesannya disamakan, supaya username gak bisa ditebak
+    let hash = user.as_ref().map(|(_, hash)| hash.as_str()).filter(|hash| !hash.is_empty());
+    let valid = verify_password(&payload.password, hash.unwrap_or_else(|| dummy_hash())) && hash.is_some();
+    let Some((user_id, _)) = user.filter(|_| valid) else {
+        record_login_failure(username);
+        return Err(AppError::unauthorized("Username atau password salah"));
+    };
+    clear_login_failures(username);
+
+    let cookie = start_session(&pool, user_id).await?;
+    Ok(([(header::SET_COOKIE, cookie)], Json("Login berhasil".to_string())))
+}
+
+fn login_failures() -> std::sync::MutexGuard<'static, HashMap<String, (u32, Instant)>> {
+    let failures = LOGIN_FAILURES.get_or_init(|| Mutex::new(HashMap::new()));
+    failures.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn login_blocked(username: &str) -> bool {
+    login_failures()
+        .get(username)
+        .is_some_and(|(count, since)| *count >= LOGIN_MAX_FAILURES && since.elapsed() < LOGIN_WINDOW)
+}
+
+fn record_login_failure(username: &str) {
+    let mut failures = login_failures();
+    // Username asal-asalan jangan sampai menumpuk di memori
+    failures.retain(|_, (_, since)| since.elapsed() < LOGIN_WINDOW);
+    let entry = failures.entry(username.to_string()).or_insert((0, Instant::now()));
+    entry.0 += 1;
+}
+
+fn clear_login_failures(username: &str) {
+    login_failures().remove(username);
+}
+
+// LOGOUT
+pub async fn logout(
+    State(pool): State<SqlitePool>,
+    headers: HeaderMap,
+) -> AppResult<impl IntoResponse> {
+    if let Some(session) = session_cookie(&headers) {
+        sqlx::query("DELETE FROM sessions WHERE token_hash = ?")
+            .bind(hash_token(&session))
+            .execute(&pool)
+            .await?;
+    }
+
+    let expired = cookie("", 0);
+    Ok(([(header::SET_COOKIE, expired)], Json("Logout berhasil".to_string())))
+}
+
+#[derive(Deserialize)]
+pub struct ChangePasswordRequest {
+    pub current_password: String,
+    pub new_password: String,
+}
+
+// GANTI PASSWORD (semua session lain ikut dicabut)
+pub async fn change_password(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<ChangePasswordRequest>,
+) -> AppResult<impl IntoResponse> {
+    let hash: String = sqlx::query_scalar("SELECT password_hash FROM users WHERE id = ?")
+        .bind(user.id)
+        .fetch_one(&pool)
+        .await?;
+
+    if !verify_password(&payload.current_password, &hash) {
+        return Err(AppError::unauthorized("Password lama salah"));
+    }
+    check_credentials(&user.username, &payload.new_password)?;
+
+    let new_hash = hash_password(&payload.new_password)?;
+    sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
+        .bind(new_hash)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+
+    let cookie = start_session(&pool, user.id).await?;
+    Ok(([(header::SET_COOKIE, cookie)], Json("Password diganti".to_string())))
+}
+
+// ==========================================
+// HANDLER API TOKEN
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ApiToken {
+    pub id: i64,
+    pub name: String,
+    pub token_prefix: String,
+    #[sqlx(json)]
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct CreatedToken {
+    pub token: String, // Cuma ditampilkan sekali ini
+    #[serde(flatten)]
+    pub info: ApiToken,
+}
+
+async fn fetch_token(pool: &SqlitePool, user_id: i64, id: i64) -> Result<Option<ApiToken>, sqlx::Error> {
+    sqlx::query_as::<_, ApiToken>(
+        "SELECT id, name, token_prefix, scopes, CAST(created_at AS TEXT) as created_at, CAST(last_used_at AS TEXT) as last_used_at
+         FROM api_tokens WHERE id = ? AND user_id = ? AND revoked = FALSE"
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+}
+
+// GET token milik user
+pub async fn list_tokens(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<ApiToken>>> {
+    let tokens = sqlx::query_as::<_, ApiToken>(
+        "SELECT id, name, token_prefix, scopes, CAST(created_at AS TEXT) as created_at, CAST(last_used_at AS TEXT) as last_used_at
+         FROM api_tokens WHERE user_id = ? AND revoked = FALSE ORDER BY id ASC"
+    )
+    .bind(user.id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(tokens))
+}
+
+// CREATE token baru
+pub async fn create_token(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<CreateTokenRequest>,
+) -> AppResult<(StatusCode, Json<CreatedToken>)> {
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::bad_request("Nama token wajib diisi"));
+    }
+    if payload.scopes.is_empty() {
+        return Err(AppError::bad_request(format!("Pilih minimal satu scope: {}", SCOPES.join(", "))));
+    }
+    if let Some(unknown) = payload.scopes.iter().find(|s| !SCOPES.contains(&s.as_str())) {
+        return Err(AppError::bad_request(format!("Scope '{}' tidak dikenal, pilih dari: {}", unknown, SCOPES.join(", "))));
+    }
+    // Token gak boleh punya akses lebih tinggi dari pembuatnya
+    if let Some(denied) = payload.scopes.iter().find(|s| !user.has_scope(s)) {
+        return Err(AppError::forbidden(format!("Tidak boleh membuat token dengan scope '{}'", denied)));
+    }
+
+    let token = format!("{}{}", TOKEN_PREFIX, random_token());
+    let id = sqlx::query(
+        "INSERT INTO api_tokens (user_id, name, token_prefix, token_hash, scopes) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(user.id)
+    .bind(name)
+    .bind(token.chars().take(TOKEN_PREFIX.len() + 6).collect::<String>())
+    .bind(hash_token(&token))
+    .bind(serde_json::to_string(&payload.scopes).unwrap_or("[]".to_string()))
+    .execute(&pool)
+    .await?
+    .last_insert_rowid();
+
+    let info = fetch_token(&pool, user.id, id).await?
+        .ok_or(AppError::internal("Error auth", "token hilang setelah dibuat"))?;
+
+    Ok((StatusCode::CREATED, Json(CreatedToken { token, info })))
+}
+
+// REVOKE token
+pub async fn revoke_token(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<String>> {
+    let result = sqlx::query("UPDATE api_tokens SET revoked = TRUE WHERE id = ? AND user_id = ? AND revoked = FALSE")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Token #{} tidak ditemukan", id)));
+    }
+
+    Ok(Json(format!("Token #{} dicabut", id)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn login_is_blocked_after_repeated_failures() {
+        let username = "tests-login-limit";
+        for _ in 0..LOGIN_MAX_FAILURES - 1 {
+            record_login_failure(username);
+        }
+        assert!(!login_blocked(username));
+
+        record_login_failure(username);
+        assert!(login_blocked(username));
+        assert!(!login_blocked("tests-login-limit-lain"));
+
+        clear_login_failures(username);
+        assert!(!login_blocked(username));
+    }
+
+    #[test]
+    fn dummy_hash_never_matches() {
+        assert!(!verify_password("", dummy_hash()));
+        assert!(!verify_password("password123", dummy_hash()));
+    }
+}
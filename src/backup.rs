@@ -0,0 +1,247 @@
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::auth::AuthUser;
+use crate::config::BackupConfig;
+use crate::error::{AppError, AppResult};
+
+// Nama file: noty-20240131-230000.db -> urut nama = urut waktu
+const PREFIX: &str = "noty-";
+const EXTENSION: &str = ".db";
+const TIMESTAMP: &str = "%Y%m%d-%H%M%S";
+
+// Seberapa sering worker mengecek apakah sudah waktunya backup
+const CHECK_EVERY: Duration = Duration::from_secs(10 * 60);
+
+static CONFIG: OnceLock<BackupConfig> = OnceLock::new();
+
+// Dipanggil sekali di main setelah config dibaca
+pub fn init(config: BackupConfig) {
+    let _ = CONFIG.set(config);
+}
+
+fn config() -> &'static BackupConfig {
+    CONFIG.get_or_init(BackupConfig::default)
+}
+
+#[derive(Serialize)]
+pub struct BackupInfo {
+    pub file: String,
+    pub size_bytes: u64,
+    pub created_at: String,
+}
+
+// ==========================================
+// BACKUP & ROTASI
+// ==========================================
+
+// VACUUM INTO = salinan konsisten walau server sedang jalan (bukan copy file mentah)
+pub async fn create_backup(pool: &SqlitePool) -> Result<BackupInfo, Box<dyn std::error::Error + Send + Sync>> {
+    let dir = &config().dir;
+    tokio::fs::create_dir_all(dir).await?;
+
+    let now = chrono::Local::now();
+    let path = dir.join(format!("{}{}{}", PREFIX, now.format(TIMESTAMP), EXTENSION));
+    if tokio::fs::try_exists(&path).await? {
+        return Err(format!("Backup {} sudah ada, coba lagi sebentar", path.display()).into());
+    }
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(path.to_string_lossy().to_string())
+        .execute(pool)
+        .await?;
+
+    let removed = rotate(dir, config().keep).await?;
+    if removed > 0 {
+        println!("🧹 {} backup lama dihapus.", removed);
+    }
+
+    let info = describe(&path).await?;
+    println!("💾 Backup dibuat: {} ({} KB)", info.file, info.size_bytes / 1024);
+    Ok(info)
+}
+
+// File backup di folder, terbaru dulu
+async fn list_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+        Err(e) => return Err(e),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(PREFIX) && name.ends_with(EXTENSION) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    files.reverse();
+    Ok(files)
+}
+
+async fn rotate(dir: &Path, keep: usize) -> std::io::Result<usize> {
+    let files = list_files(dir).await?;
+    let mut removed = 0;
+    for old in files.iter().skip(keep) {
+        tokio::fs::remove_file(old).await?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+async fn describe(path: &Path) -> std::io::Result<BackupInfo> {
+    let meta = tokio::fs::metadata(path).await?;
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let created_at = backup_time(&name)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default();
+    Ok(BackupInfo { file: name, size_bytes: meta.len(), created_at })
+}
+
+fn backup_time(name: &str) -> Option<chrono::NaiveDateTime> {
+    let stamp = name.strip_prefix(PREFIX)?.strip_suffix(EXTENSION)?;
+    chrono::NaiveDateTime::parse_from_str(stamp, TIMESTAMP).ok()
+}
+
+// Worker backup terjadwal. Waktu backup terakhir dibaca dari nama file,
+// jadi restart server tidak memicu backup baru setiap kali.
+pub async fn start_scheduler(pool: SqlitePool) {
+    let interval_hours = config().interval_hours;
+    if interval_hours == 0 {
+        println!("💾 Backup terjadwal dimatikan (backup.interval_hours = 0).");
+        return;
+    }
+    let interval = chrono::Duration::hours(interval_hours as i64);
+
+    loop {
+        let latest = list_files(&config().dir).await.unwrap_or_default()
+            .first()
+            .and_then(|p| p.file_name())
+            .and_then(|n| backup_time(&n.to_string_lossy()));
+
+        let due = latest.is_none_or(|t| chrono::Local::now().naive_local() - t >= interval);
+        if due {
+            if let Err(e) = create_backup(&pool).await {
+                eprintln!("❌ Backup terjadwal gagal: {}", e);
+            }
+        }
+
+        tokio::time::sleep(CHECK_EVERY).await;
+    }
+}
+
+// ==========================================
+// API ADMIN
+// ==========================================
+
+// POST /api/admin/backup -> backup sekarang juga
+pub async fn backup_now(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<(StatusCode, Json<BackupInfo>)> {
+    crate::users::require_admin(&user, "membuat backup")?;
+
+    let info = create_backup(&pool).await.map_err(|e| AppError::internal("Backup gagal", e).with_code("backup_failed"))?;
+    Ok((StatusCode::CREATED, Json(info)))
+}
+
+// GET /api/admin/backups -> daftar backup yang tersimpan
+pub async fn list_backups(
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<BackupInfo>>> {
+    crate::users::require_admin(&user, "melihat daftar backup")?;
+
+    let files = list_files(&config().dir).await.map_err(|e| AppError::internal("Gagal membaca folder backup", e))?;
+    let mut backups = Vec::with_capacity(files.len());
+    for file in files {
+        backups.push(describe(&file).await.map_err(|e| AppError::internal("Gagal membaca backup", e))?);
+    }
+    Ok(Json(backups))
+}
+
+// ==========================================
+// RESTORE (CLI: `noty restore <file>`)
+// ==========================================
+
+// Cek backup sebelum dipakai: file SQLite utuh, punya tabel inti, dan versi skemanya
+// tidak lebih baru dari binary ini (versi lama aman, dimigrasi saat startup).
+async fn validate_backup(path: &Path) -> Result<i64, Box<dyn std::error::Error>> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await
+        .map_err(|e| format!("{} bukan database SQLite yang bisa dibuka: {}", path.display(), e))?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check").fetch_one(&pool).await
+        .map_err(|e| format!("{} bukan database SQLite yang valid: {}", path.display(), e))?;
+    if integrity != "ok" {
+        return Err(format!("Backup rusak (integrity_check: {})", integrity).into());
+    }
+
+    let has_logbook: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'log_entries')")
+        .fetch_one(&pool)
+        .await?;
+    if !has_logbook {
+        return Err("Backup tidak berisi tabel log_entries, bukan database Noty".into());
+    }
+
+    let version = crate::db::schema_version(&pool).await?;
+    pool.close().await;
+
+    if version > crate::db::SCHEMA_VERSION {
+        return Err(format!(
+            "Backup berasal dari skema versi {}, binary ini baru mendukung sampai versi {}. Update Noty dulu.",
+            version,
+            crate::db::SCHEMA_VERSION
+        ).into());
+    }
+    Ok(version)
+}
+
+// Ganti database aktif dengan file backup. Server harus dalam keadaan mati.
+pub async fn restore(database_url: &str, backup: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let version = validate_backup(backup).await?;
+    println!("✅ Backup valid (skema versi {}).", version);
+
+    let target = SqliteConnectOptions::from_str(database_url)?.get_filename().to_path_buf();
+
+    // Database yang sekarang diamankan dulu, supaya restore yang salah masih bisa dibatalkan
+    if tokio::fs::try_exists(&target).await? {
+        let dir = &config().dir;
+        tokio::fs::create_dir_all(dir).await?;
+        let safety = dir.join(format!("pre-restore-{}{}", chrono::Local::now().format(TIMESTAMP), EXTENSION));
+
+        let current = SqlitePoolOptions::new().max_connections(1).connect_with(SqliteConnectOptions::new().filename(&target)).await?;
+        sqlx::query("VACUUM INTO ?").bind(safety.to_string_lossy().to_string()).execute(&current).await?;
+        current.close().await;
+        println!("💾 Database lama disimpan di {}", safety.display());
+    }
+
+    // Copy ke file sementara di folder yang sama lalu rename (atomic di filesystem yang sama)
+    let staged = target.with_extension("db.restoring");
+    tokio::fs::copy(backup, &staged).await?;
+    tokio::fs::File::open(&staged).await?.sync_all().await?;
+    tokio::fs::rename(&staged, &target).await?;
+
+    // WAL/SHM milik database lama gak boleh ikut terbaca oleh database hasil restore
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = target.clone().into_os_string();
+        sidecar.push(suffix);
+        let _ = tokio::fs::remove_file(sidecar).await;
+    }
+
+    println!("♻️  Database {} dipulihkan dari {}.", target.display(), backup.display());
+    println!("   Secret (API key, token) tetap butuh master key yang sama dengan saat backup dibuat.");
+    Ok(())
+}
@@ -0,0 +1,524 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError, JsonBody};
+
+// Kategori bawaan untuk log tanpa kategori; tidak bisa dihapus/di-rename
+pub const DEFAULT_CATEGORY: &str = "General";
+
+const NAME_MAX: usize = 50;
+const ICON_MAX: usize = 32;
+
+// log_entries.category tetap menyimpan nama (bukan id) supaya export, webhook & Notion
+// tidak berubah. Tabel ini jadi sumber ejaan resmi, warna, ikon & hierarki.
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS categories (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            name TEXT NOT NULL COLLATE NOCASE, -- 'kerja' dan 'Kerja' dianggap sama
+            color TEXT,                        -- #RRGGBB
+            icon TEXT,                         -- Emoji atau nama ikon di UI
+            parent_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(user_id, name)
+        );"
+    ).execute(pool).await?;
+
+    Ok(())
+}
+
+// Dipanggil lewat users::ensure_defaults: kategori bawaan + semua kategori yang sudah dipakai log
+pub async fn seed_defaults(pool: &SqlitePool, user_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT OR IGNORE INTO categories (user_id, name) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(DEFAULT_CATEGORY)
+        .execute(pool)
+        .await?;
+    sync_from_entries(pool, user_id).await
+}
+
+// Kategori baru dari jalur yang tidak lewat resolve() (misal import massal)
+pub async fn sync_from_entries(pool: &SqlitePool, user_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT OR IGNORE INTO categories (user_id, name)
+         SELECT DISTINCT user_id, TRIM(category) FROM log_entries
+         WHERE user_id = ? AND category IS NOT NULL AND TRIM(category) != ''"
+    )
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// Ejaan resmi kategori untuk log baru; kategori yang belum ada otomatis dibuat
+pub async fn resolve(pool: &SqlitePool, user_id: i64, name: Option<&str>) -> Result<String, sqlx::Error> {
+    let name = name.map(str::trim).filter(|n| !n.is_empty()).unwrap_or(DEFAULT_CATEGORY);
+
+    sqlx::query("INSERT OR IGNORE INTO categories (user_id, name) VALUES (?, ?)")
+        .bind(user_id)
+        .bind(name)
+        .execute(pool)
+        .await?;
+    sqlx::query_scalar("SELECT name FROM categories WHERE user_id = ? AND name = ?")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(pool)
+        .await
+}
+
+// ==========================================
+// MODEL
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub parent_id: Option<i64>,
+    pub entry_count: i64,
+    pub created_at: String,
+}
+
+const CATEGORY_COLUMNS: &str = "c.id, c.name, c.color, c.icon, c.parent_id,
+    (SELECT COUNT(*) FROM log_entries l WHERE l.user_id = c.user_id AND l.is_deleted = FALSE AND l.category = c.name COLLATE NOCASE) as entry_count,
+    CAST(c.created_at AS TEXT) as created_at";
+
+#[derive(Deserialize)]
+pub struct CategoryRequest {
+    pub name: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct MergeRequest {
+    pub into_id: i64, // Kategori tujuan; kategori sumber dihapus setelah digabung
+}
+
+#[derive(Serialize)]
+pub struct MergeResult {
+    pub category: Category,
+    pub moved_entries: u64,
+}
+
+struct ValidCategory {
+    name: String,
+    color: Option<String>,
+    icon: Option<String>,
+    parent_id: Option<i64>,
+}
+
+async fn find(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<Category> {
+    sqlx::query_as::<_, Category>(&format!("SELECT {} FROM categories c WHERE c.id = ? AND c.user_id = ?", CATEGORY_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Kategori #{} tidak ditemukan", id)))
+}
+
+// `id` = kategori yang sedang diedit (None saat membuat baru), untuk cegah parent melingkar
+async fn validate(pool: &SqlitePool, user_id: i64, id: Option<i64>, payload: CategoryRequest) -> AppResult<ValidCategory> {
+    let mut errors = Vec::new();
+
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        errors.push(FieldError::new("name", "Nama kategori wajib diisi"));
+    } else if name.chars().count() > NAME_MAX {
+        errors.push(FieldError::new("name", format!("Maksimal {} karakter", NAME_MAX)));
+    }
+
+    let color = payload.color.map(|c| c.trim().to_lowercase()).filter(|c| !c.is_empty());
+    if let Some(color) = &color {
+        let valid = color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !valid {
+            errors.push(FieldError::new("color", format!("Warna harus format #RRGGBB, dapat '{}'", color)));
+        }
+    }
+
+    let icon = payload.icon.map(|i| i.trim().to_string()).filter(|i| !i.is_empty());
+    if icon.as_ref().is_some_and(|i| i.chars().count() > ICON_MAX) {
+        errors.push(FieldError::new("icon", format!("Maksimal {} karakter", ICON_MAX)));
+    }
+
+    if let Some(parent_id) = payload.parent_id {
+        // Naik terus dari calon parent; kalau ketemu diri sendiri berarti melingkar
+        let ancestors = lineage(pool, user_id, parent_id).await?;
+
+        if ancestors.is_empty() {
+            errors.push(FieldError::new("parent_id", format!("Kategori #{} tidak ditemukan", parent_id)));
+        } else if id.is_some_and(|id| ancestors.contains(&id)) {
+            errors.push(FieldError::new("parent_id", "Kategori tidak boleh jadi turunan dirinya sendiri"));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+    Ok(ValidCategory { name, color, icon, parent_id: payload.parent_id })
+}
+
+// Kategori `id` beserta semua leluhurnya (kosong kalau `id` tidak ada). UNION (bukan
+// UNION ALL) supaya query tetap berhenti walaupun data lama ternyata melingkar.
+async fn lineage<'e, E>(executor: E, user_id: i64, id: i64) -> Result<Vec<i64>, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query_scalar(
+        "WITH RECURSIVE up(id, parent_id) AS (
+            SELECT id, parent_id FROM categories WHERE id = ? AND user_id = ?
+            UNION SELECT c.id, c.parent_id FROM categories c JOIN up ON c.id = up.parent_id
+         ) SELECT id FROM up"
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_all(executor)
+    .await
+}
+
+// Ada kategori yang (lewat parent_id) jadi leluhur dirinya sendiri?
+async fn has_cycle<'e, E>(executor: E, user_id: i64) -> Result<bool, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query_scalar(
+        "WITH RECURSIVE up(start, id) AS (
+            SELECT id, parent_id FROM categories WHERE user_id = ? AND parent_id IS NOT NULL
+            UNION SELECT up.start, c.parent_id FROM categories c JOIN up ON c.id = up.id WHERE c.parent_id IS NOT NULL
+         ) SELECT EXISTS(SELECT 1 FROM up WHERE up.start = up.id)"
+    )
+    .bind(user_id)
+    .fetch_one(executor)
+    .await
+}
+
+fn name_taken(name: &str) -> AppError {
+    AppError::conflict(format!("Kategori '{}' sudah ada, pakai merge untuk menggabungkan", name)).with_code("category_exists")
+}
+
+fn protected() -> AppError {
+    AppError::conflict(format!("Kategori '{}' adalah kategori bawaan", DEFAULT_CATEGORY)).with_code("category_protected")
+}
+
+// ==========================================
+// API
+// ==========================================
+
+// GET /api/categories (urut nama; UI menyusun pohon dari parent_id)
+pub async fn list_categories(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<Category>>> {
+    let categories = sqlx::query_as::<_, Category>(&format!(
+        "SELECT {} FROM categories c WHERE c.user_id = ? ORDER BY c.name ASC", CATEGORY_COLUMNS
+    ))
+    .bind(user.id)
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(categories))
+}
+
+// POST /api/categories
+pub async fn create_category(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<CategoryRequest>,
+) -> AppResult<(StatusCode, Json<Category>)> {
+    let c = validate(&pool, user.id, None, payload).await?;
+
+    let result = sqlx::query("INSERT INTO categories (user_id, name, color, icon, parent_id) VALUES (?, ?, ?, ?, ?)")
+        .bind(user.id)
+        .bind(&c.name)
+        .bind(&c.color)
+        .bind(&c.icon)
+        .bind(c.parent_id)
+        .execute(&pool)
+        .await;
+
+    let id = match result {
+        Ok(r) => r.last_insert_rowid(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Err(name_taken(&c.name)),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((StatusCode::CREATED, Json(find(&pool, user.id, id).await?)))
+}
+
+// PUT /api/categories/{id} -> ganti nama ikut memperbarui semua log yang memakainya
+pub async fn update_category(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<CategoryRequest>,
+) -> AppResult<Json<Category>> {
+    let existing = find(&pool, user.id, id).await?;
+    let c = validate(&pool, user.id, Some(id), payload).await?;
+
+    let renamed = c.name != existing.name;
+    if renamed && existing.name == DEFAULT_CATEGORY {
+        return Err(protected());
+    }
+
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query("UPDATE categories SET name = ?, color = ?, icon = ?, parent_id = ? WHERE id = ? AND user_id = ?")
+        .bind(&c.name)
+        .bind(&c.color)
+        .bind(&c.icon)
+        .bind(c.parent_id)
+        .bind(id)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await;
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Err(name_taken(&c.name)),
+        Err(e) => return Err(e.into()),
+    }
+
+    // Termasuk log di trash, supaya restore dari trash tetap konsisten
+    if renamed {
+        sqlx::query("UPDATE log_entries SET category = ? WHERE user_id = ? AND category = ? COLLATE NOCASE")
+            .bind(&c.name)
+            .bind(user.id)
+            .bind(&existing.name)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(Json(find(&pool, user.id, id).await?))
+}
+
+// DELETE /api/categories/{id} -> hanya kalau tidak dipakai log; sub-kategori naik satu level
+pub async fn delete_category(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    let existing = find(&pool, user.id, id).await?;
+    if existing.name == DEFAULT_CATEGORY {
+        return Err(protected());
+    }
+
+    let in_use: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM log_entries WHERE user_id = ? AND category = ? COLLATE NOCASE")
+        .bind(user.id)
+        .bind(&existing.name)
+        .fetch_one(&pool)
+        .await?;
+    if in_use > 0 {
+        return Err(AppError::conflict(format!(
+            "Kategori '{}' masih dipakai {} log (termasuk trash), gabungkan dulu ke kategori lain", existing.name, in_use
+        )).with_code("category_in_use"));
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE categories SET parent_id = ? WHERE parent_id = ? AND user_id = ?")
+        .bind(existing.parent_id)
+        .bind(id)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM categories WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /api/categories/{id}/merge {into_id} -> semua log & sub-kategori pindah ke tujuan
+pub async fn merge_category(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<MergeRequest>,
+) -> AppResult<Json<MergeResult>> {
+    if payload.into_id == id {
+        return Err(AppError::validation(vec![FieldError::new("into_id", "Tidak bisa digabung ke dirinya sendiri")]));
+    }
+    let source = find(&pool, user.id, id).await?;
+    let target = find(&pool, user.id, payload.into_id).await?;
+    if source.name == DEFAULT_CATEGORY {
+        return Err(protected());
+    }
+
+    let mut tx = pool.begin().await?;
+    let moved = sqlx::query("UPDATE log_entries SET category = ? WHERE user_id = ? AND category = ? COLLATE NOCASE")
+        .bind(&target.name)
+        .bind(user.id)
+        .bind(&source.name)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    // Kalau tujuan adalah turunan sumber (anak, cucu, dst), tujuan naik ke posisi sumber;
+    // tanpa ini sub-kategori sumber bisa jadi parent dari leluhurnya sendiri
+    if lineage(&mut *tx, user.id, target.id).await?.contains(&id) {
+        sqlx::query("UPDATE categories SET parent_id = ? WHERE id = ? AND user_id = ?")
+            .bind(source.parent_id)
+            .bind(target.id)
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query("UPDATE categories SET parent_id = ? WHERE parent_id = ? AND user_id = ?")
+        .bind(target.id)
+        .bind(id)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM categories WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?;
+
+    // Jaring pengaman: filter sub-kategori di get_logs tidak boleh ketemu pohon melingkar
+    if has_cycle(&mut *tx, user.id).await? {
+        tx.rollback().await?;
+        return Err(AppError::conflict("Penggabungan ini akan membuat hierarki kategori melingkar").with_code("category_cycle"));
+    }
+    tx.commit().await?;
+
+    println!("🗂️ Kategori '{}' digabung ke '{}' ({} log dipindah).", source.name, target.name, moved);
+    Ok(Json(MergeResult { category: find(&pool, user.id, target.id).await?, moved_entries: moved }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: i64 = 1;
+
+    async fn setup() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT NOT NULL)").execute(&pool).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE log_entries (id INTEGER PRIMARY KEY, user_id INTEGER, content TEXT, category TEXT, is_deleted BOOLEAN DEFAULT FALSE)"
+        ).execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO users (id, username) VALUES (?, 'tester')").bind(USER).execute(&pool).await.unwrap();
+        init_schema(&pool).await.unwrap();
+        pool
+    }
+
+    fn user() -> AuthUser {
+        AuthUser { id: USER, username: "tester".to_string(), is_admin: false, scopes: Vec::new() }
+    }
+
+    async fn add(pool: &SqlitePool, name: &str, parent_id: Option<i64>) -> i64 {
+        sqlx::query("INSERT INTO categories (user_id, name, parent_id) VALUES (?, ?, ?)")
+            .bind(USER)
+            .bind(name)
+            .bind(parent_id)
+            .execute(pool)
+            .await
+            .unwrap()
+            .last_insert_rowid()
+    }
+
+    async fn parent_of(pool: &SqlitePool, id: i64) -> Option<i64> {
+        sqlx::query_scalar("SELECT parent_id FROM categories WHERE id = ?").bind(id).fetch_one(pool).await.unwrap()
+    }
+
+    async fn merge(pool: &SqlitePool, id: i64, into_id: i64) -> AppResult<MergeResult> {
+        let Json(result) = merge_category(State(pool.clone()), Extension(user()), Path(id), JsonBody(MergeRequest { into_id })).await?;
+        Ok(result)
+    }
+
+    #[tokio::test]
+    async fn merge_into_grandchild_moves_target_above_source() {
+        let pool = setup().await;
+        let a = add(&pool, "A", None).await;
+        let b = add(&pool, "B", Some(a)).await;
+        let c = add(&pool, "C", Some(b)).await;
+
+        merge(&pool, a, c).await.unwrap();
+
+        assert_eq!(parent_of(&pool, c).await, None);
+        assert_eq!(parent_of(&pool, b).await, Some(c));
+        assert!(!has_cycle(&pool, USER).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn merge_into_child_inherits_source_parent() {
+        let pool = setup().await;
+        let root = add(&pool, "Root", None).await;
+        let a = add(&pool, "A", Some(root)).await;
+        let b = add(&pool, "B", Some(a)).await;
+        let sibling = add(&pool, "Sibling", Some(a)).await;
+
+        merge(&pool, a, b).await.unwrap();
+
+        assert_eq!(parent_of(&pool, b).await, Some(root));
+        assert_eq!(parent_of(&pool, sibling).await, Some(b));
+        assert!(!has_cycle(&pool, USER).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn merge_moves_entries_and_deletes_source() {
+        let pool = setup().await;
+        let a = add(&pool, "Kerja", None).await;
+        let b = add(&pool, "Kantor", None).await;
+        for category in ["kerja", "Kerja", "Kantor"] {
+            sqlx::query("INSERT INTO log_entries (user_id, content, category) VALUES (?, 'x', ?)")
+                .bind(USER)
+                .bind(category)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let result = merge(&pool, a, b).await.unwrap();
+
+        assert_eq!(result.moved_entries, 2);
+        assert_eq!(result.category.entry_count, 3);
+        assert!(lineage(&pool, USER, a).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn merge_into_itself_is_rejected() {
+        let pool = setup().await;
+        let a = add(&pool, "A", None).await;
+
+        let err = merge(&pool, a, a).await.err().unwrap();
+        assert_eq!(err.code, "validation_failed");
+    }
+
+    #[tokio::test]
+    async fn parent_cannot_be_own_descendant() {
+        let pool = setup().await;
+        let a = add(&pool, "A", None).await;
+        let b = add(&pool, "B", Some(a)).await;
+        let c = add(&pool, "C", Some(b)).await;
+
+        let request = CategoryRequest { name: "A".to_string(), color: None, icon: None, parent_id: Some(c) };
+        let err = validate(&pool, USER, Some(a), request).await.err().unwrap();
+        assert_eq!(err.fields[0].field, "parent_id");
+    }
+
+    #[tokio::test]
+    async fn cycle_detection_terminates_on_existing_cycle() {
+        let pool = setup().await;
+        let a = add(&pool, "A", None).await;
+        let b = add(&pool, "B", Some(a)).await;
+        assert!(!has_cycle(&pool, USER).await.unwrap());
+
+        sqlx::query("UPDATE categories SET parent_id = ? WHERE id = ?").bind(b).bind(a).execute(&pool).await.unwrap();
+
+        assert!(has_cycle(&pool, USER).await.unwrap());
+        assert_eq!(lineage(&pool, USER, a).await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,357 @@
+use axum::http::HeaderValue;
+use serde::Deserialize;
+use std::{env, fs, net::{IpAddr, SocketAddr}, path::{Path, PathBuf}, str::FromStr};
+
+// Konfigurasi server. Urutan prioritas: default -> file noty.toml -> env variable.
+//
+// Contoh noty.toml:
+//   log_level = "info"
+//   [server]
+//   bind_address = "127.0.0.1"
+//   port = 3000
+//   cors_origins = ["http://localhost:5173"]
+//   ui_dir = "ui/dist"
+//   [server.tls]
+//   cert_path = "/etc/noty/cert.pem"
+//   key_path = "/etc/noty/key.pem"
+//   [database]
+//   url = "sqlite://noty.db?mode=rwc"
+//   max_connections = 5
+//   [logbook]
+//   max_content_length = 10000
+//   extra_sources = ["Shortcut-iOS"]
+//   extra_moods = ["Grateful"]
+//   [backup]
+//   dir = "backups"
+//   interval_hours = 24
+//   keep = 7
+//   [attachments]
+//   dir = "attachments"
+//   max_size_mb = 20
+const DEFAULT_CONFIG_FILE: &str = "noty.toml";
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub log_level: String, // trace, debug, info, warn, error
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub logbook: LogbookConfig,
+    pub backup: BackupConfig,
+    pub attachments: AttachmentsConfig,
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind_address: String,
+    pub port: u16,
+    pub cors_origins: Vec<String>, // Origin UI yang boleh kirim cookie session
+    pub ui_dir: PathBuf,           // Hasil `npm run build`, dipakai kalau UI tidak di-embed
+    pub tls: Option<TlsConfig>,    // Kosong = HTTP biasa
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TlsConfig {
+    pub cert_path: PathBuf, // PEM, boleh berisi chain
+    pub key_path: PathBuf,  // PEM (PKCS#8 / RSA / EC)
+}
+
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct LogbookConfig {
+    pub max_content_length: usize, // Dihitung per karakter, bukan byte
+    pub extra_sources: Vec<String>, // Tambahan di luar source bawaan (Manual, GitHub, ...)
+    pub extra_moods: Vec<String>,   // Tambahan di luar mood bawaan (Neutral, Productive, ...)
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    pub interval_hours: u64, // 0 = backup terjadwal mati (manual lewat API tetap bisa)
+    pub keep: usize,         // Jumlah backup terjadwal/manual yang disimpan, sisanya dihapus
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct AttachmentsConfig {
+    pub dir: PathBuf,       // File disimpan per hash SHA-256, bukan per nama
+    pub max_size_mb: usize, // Batas per upload (total semua file dalam satu request)
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            log_level: "info".to_string(),
+            server: ServerConfig::default(),
+            database: DatabaseConfig::default(),
+            logbook: LogbookConfig::default(),
+            backup: BackupConfig::default(),
+            attachments: AttachmentsConfig::default(),
+        }
+    }
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind_address: "0.0.0.0".to_string(),
+            port: 3000,
+            cors_origins: vec!["http://localhost:5173".to_string()],
+            ui_dir: PathBuf::from("ui/dist"),
+            tls: None,
+        }
+    }
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            url: "sqlite://noty.db?mode=rwc".to_string(),
+            max_connections: 5,
+        }
+    }
+}
+
+impl Config {
+    pub fn socket_addr(&self) -> SocketAddr {
+        // Sudah dicek di validate()
+        let ip = IpAddr::from_str(&self.server.bind_address).unwrap_or(IpAddr::from([0, 0, 0, 0]));
+        SocketAddr::new(ip, self.server.port)
+    }
+
+    pub fn cors_origins(&self) -> Vec<HeaderValue> {
+        self.server.cors_origins.iter().filter_map(|o| o.parse().ok()).collect()
+    }
+
+    pub fn log_level(&self) -> tracing::Level {
+        tracing::Level::from_str(&self.log_level).unwrap_or(tracing::Level::INFO)
+    }
+
+    // URL yang ditampilkan di log startup
+    pub fn public_url(&self) -> String {
+        let scheme = if self.server.tls.is_some() { "https" } else { "http" };
+        let host = match self.server.bind_address.as_str() {
+            "0.0.0.0" | "::" => "localhost",
+            other => other,
+        };
+        format!("{}://{}:{}", scheme, host, self.server.port)
+    }
+}
+
+impl Default for LogbookConfig {
+    fn default() -> Self {
+        LogbookConfig {
+            max_content_length: 10_000,
+            extra_sources: Vec::new(),
+            extra_moods: Vec::new(),
+        }
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            dir: PathBuf::from("backups"),
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+impl Default for AttachmentsConfig {
+    fn default() -> Self {
+        AttachmentsConfig {
+            dir: PathBuf::from("attachments"),
+            max_size_mb: 20,
+        }
+    }
+}
+
+impl AttachmentsConfig {
+    pub fn max_bytes(&self) -> usize {
+        self.max_size_mb * 1024 * 1024
+    }
+}
+
+// ==========================================
+// LOAD & VALIDASI
+// ==========================================
+
+// File config: NOTY_CONFIG (wajib ada kalau diset), atau noty.toml di folder kerja (opsional)
+fn read_file() -> Result<Option<(PathBuf, String)>, String> {
+    let (path, required) = match env::var("NOTY_CONFIG") {
+        Ok(path) => (PathBuf::from(path), true),
+        Err(_) => (PathBuf::from(DEFAULT_CONFIG_FILE), false),
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(raw) => Ok(Some((path, raw))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && !required => Ok(None),
+        Err(e) => Err(format!("Gagal membaca file config {}: {}", path.display(), e)),
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn env_parse<T: FromStr>(name: &str, errors: &mut Vec<String>) -> Option<T> {
+    let raw = env_var(name)?;
+    match raw.parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            errors.push(format!("{}: nilai '{}' tidak valid", name, raw));
+            None
+        }
+    }
+}
+
+fn env_list(name: &str) -> Option<Vec<String>> {
+    env_var(name).map(|raw| raw.split(',').map(|v| v.trim().to_string()).filter(|v| !v.is_empty()).collect())
+}
+
+fn apply_env(config: &mut Config, errors: &mut Vec<String>) {
+    if let Some(level) = env_var("NOTY_LOG_LEVEL") {
+        config.log_level = level;
+    }
+    if let Some(address) = env_var("NOTY_BIND_ADDRESS") {
+        config.server.bind_address = address;
+    }
+    if let Some(port) = env_parse("NOTY_PORT", errors) {
+        config.server.port = port;
+    }
+    if let Some(origins) = env_list("NOTY_CORS_ORIGINS") {
+        config.server.cors_origins = origins;
+    }
+    if let Some(dir) = env_var("NOTY_UI_DIR") {
+        config.server.ui_dir = dir.into();
+    }
+    if let Some(url) = env_var("DATABASE_URL") {
+        config.database.url = url;
+    }
+    if let Some(max) = env_parse("NOTY_DB_MAX_CONNECTIONS", errors) {
+        config.database.max_connections = max;
+    }
+    if let Some(max) = env_parse("NOTY_MAX_CONTENT_LENGTH", errors) {
+        config.logbook.max_content_length = max;
+    }
+    if let Some(sources) = env_list("NOTY_EXTRA_SOURCES") {
+        config.logbook.extra_sources = sources;
+    }
+    if let Some(moods) = env_list("NOTY_EXTRA_MOODS") {
+        config.logbook.extra_moods = moods;
+    }
+    if let Some(dir) = env_var("NOTY_BACKUP_DIR") {
+        config.backup.dir = dir.into();
+    }
+    if let Some(hours) = env_parse("NOTY_BACKUP_INTERVAL_HOURS", errors) {
+        config.backup.interval_hours = hours;
+    }
+    if let Some(keep) = env_parse("NOTY_BACKUP_KEEP", errors) {
+        config.backup.keep = keep;
+    }
+    if let Some(dir) = env_var("NOTY_ATTACHMENTS_DIR") {
+        config.attachments.dir = dir.into();
+    }
+    if let Some(max) = env_parse("NOTY_ATTACHMENT_MAX_MB", errors) {
+        config.attachments.max_size_mb = max;
+    }
+
+    match (env_var("NOTY_TLS_CERT"), env_var("NOTY_TLS_KEY")) {
+        (Some(cert), Some(key)) => config.server.tls = Some(TlsConfig { cert_path: cert.into(), key_path: key.into() }),
+        (None, None) => {}
+        _ => errors.push("NOTY_TLS_CERT dan NOTY_TLS_KEY harus diisi berdua".to_string()),
+    }
+}
+
+fn check_file(label: &str, path: &Path, errors: &mut Vec<String>) {
+    match fs::metadata(path) {
+        Ok(meta) if meta.is_file() => {}
+        Ok(_) => errors.push(format!("{}: {} bukan file", label, path.display())),
+        Err(e) => errors.push(format!("{}: gagal membuka {} ({})", label, path.display(), e)),
+    }
+}
+
+fn validate(config: &Config, errors: &mut Vec<String>) {
+    if tracing::Level::from_str(&config.log_level).is_err() {
+        errors.push(format!("log_level '{}' tidak dikenal, pilih: trace, debug, info, warn, error", config.log_level));
+    }
+
+    if IpAddr::from_str(&config.server.bind_address).is_err() {
+        errors.push(format!("server.bind_address '{}' bukan alamat IP (contoh: 127.0.0.1 atau 0.0.0.0)", config.server.bind_address));
+    }
+    if config.server.port == 0 {
+        errors.push("server.port tidak boleh 0".to_string());
+    }
+
+    for origin in &config.server.cors_origins {
+        // Origin = scheme + host (+ port), tanpa path. "*" gak bisa dipakai bareng cookie.
+        let valid = reqwest::Url::parse(origin)
+            .map(|u| matches!(u.scheme(), "http" | "https") && u.has_host() && u.path() == "/" && !origin.ends_with('/'))
+            .unwrap_or(false);
+        if !valid {
+            errors.push(format!("server.cors_origins: '{}' bukan origin valid (contoh: https://noty.example.com)", origin));
+        }
+    }
+
+    if let Some(tls) = &config.server.tls {
+        check_file("server.tls.cert_path", &tls.cert_path, errors);
+        check_file("server.tls.key_path", &tls.key_path, errors);
+    }
+
+    if !config.database.url.starts_with("sqlite:") {
+        errors.push(format!("database.url harus diawali 'sqlite:', dapat '{}'", config.database.url));
+    }
+    if !(1..=100).contains(&config.database.max_connections) {
+        errors.push(format!("database.max_connections harus 1-100, dapat {}", config.database.max_connections));
+    }
+
+    if config.logbook.max_content_length == 0 {
+        errors.push("logbook.max_content_length tidak boleh 0".to_string());
+    }
+    if config.backup.keep == 0 {
+        errors.push("backup.keep minimal 1".to_string());
+    }
+    if config.backup.dir.as_os_str().is_empty() {
+        errors.push("backup.dir tidak boleh kosong".to_string());
+    }
+    if config.attachments.dir.as_os_str().is_empty() {
+        errors.push("attachments.dir tidak boleh kosong".to_string());
+    }
+    if !(1..=1024).contains(&config.attachments.max_size_mb) {
+        errors.push(format!("attachments.max_size_mb harus 1-1024, dapat {}", config.attachments.max_size_mb));
+    }
+    for (label, values) in [("logbook.extra_sources", &config.logbook.extra_sources), ("logbook.extra_moods", &config.logbook.extra_moods)] {
+        if values.iter().any(|v| v.trim().is_empty()) {
+            errors.push(format!("{}: nilai kosong tidak boleh", label));
+        }
+    }
+}
+
+// Semua kesalahan dikumpulkan sekaligus supaya gak perlu bolak-balik restart
+pub fn load() -> Result<Config, String> {
+    let mut config = match read_file()? {
+        Some((path, raw)) => toml::from_str::<Config>(&raw)
+            .map_err(|e| format!("File config {} tidak valid:\n{}", path.display(), e))?,
+        None => Config::default(),
+    };
+
+    let mut errors = Vec::new();
+    apply_env(&mut config, &mut errors);
+    validate(&config, &mut errors);
+
+    if !errors.is_empty() {
+        return Err(format!("Konfigurasi tidak valid:\n  - {}", errors.join("\n  - ")));
+    }
+    Ok(config)
+}
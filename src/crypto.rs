@@ -0,0 +1,334 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose::STANDARD as B64, Engine};
+use sqlx::SqlitePool;
+use std::{env, fs, path::PathBuf, sync::OnceLock};
+
+// Envelope encryption untuk semua secret di database (API key, token, URL webhook):
+// tiap nilai dienkripsi pakai data key (DEK) acak, lalu DEK-nya dibungkus pakai master key.
+// Ganti master key cukup membungkus ulang DEK, isi secret gak perlu disentuh.
+//
+// Format di DB: "enc:v1:<base64(nonce + DEK terbungkus)>:<base64(nonce + ciphertext)>"
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+// Kolom yang isinya secret: (tabel, kolom)
+const SECRET_COLUMNS: &[(&str, &str)] = &[
+    ("app_settings", "ai_api_key"),
+    ("integrations", "api_key"),
+];
+
+static MASTER_KEY: OnceLock<Key<Aes256Gcm>> = OnceLock::new();
+
+// ==========================================
+// MASTER KEY
+// ==========================================
+
+// Lokasi keyfile: NOTY_KEY_FILE, atau ~/.config/noty/master.key
+fn key_file_path() -> PathBuf {
+    if let Ok(path) = env::var("NOTY_KEY_FILE") {
+        return PathBuf::from(path);
+    }
+    let config_dir = env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| env::var("HOME").map(|h| PathBuf::from(h).join(".config")))
+        .unwrap_or_else(|_| PathBuf::from("."));
+    config_dir.join("noty").join("master.key")
+}
+
+fn parse_key(encoded: &str) -> Result<Key<Aes256Gcm>, String> {
+    let bytes = B64.decode(encoded.trim()).map_err(|e| format!("Master key bukan base64 valid: {}", e))?;
+    if bytes.len() != 32 {
+        return Err(format!("Master key harus 32 byte, dapat {} byte", bytes.len()));
+    }
+    Ok(*Key::<Aes256Gcm>::from_slice(&bytes))
+}
+
+fn write_key_file(path: &PathBuf, key: &Key<Aes256Gcm>) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Gagal membuat folder {}: {}", dir.display(), e))?;
+    }
+    fs::write(path, B64.encode(key)).map_err(|e| format!("Gagal menulis {}: {}", path.display(), e))?;
+
+    // Cuma pemilik file yang boleh baca
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Gagal set permission {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+// Urutan: env NOTY_MASTER_KEY -> keyfile -> generate keyfile baru
+fn load_master_key() -> Result<Key<Aes256Gcm>, String> {
+    if let Ok(encoded) = env::var("NOTY_MASTER_KEY") {
+        return parse_key(&encoded);
+    }
+
+    let path = key_file_path();
+    match fs::read_to_string(&path) {
+        Ok(encoded) => parse_key(&encoded),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let key = Aes256Gcm::generate_key(OsRng);
+            write_key_file(&path, &key)?;
+            println!("🔑 Master key baru dibuat di {} (backup file ini, tanpa ini secret di DB tidak bisa dibaca)", path.display());
+            Ok(key)
+        }
+        Err(e) => Err(format!("Gagal membaca {}: {}", path.display(), e)),
+    }
+}
+
+pub fn init() -> Result<(), String> {
+    let key = load_master_key()?;
+    let _ = MASTER_KEY.set(key);
+    Ok(())
+}
+
+fn master_key() -> &'static Key<Aes256Gcm> {
+    MASTER_KEY.get().expect("crypto::init() belum dipanggil")
+}
+
+// ==========================================
+// ENKRIPSI / DEKRIPSI
+// ==========================================
+
+fn seal(key: &Key<Aes256Gcm>, plain: &[u8]) -> Vec<u8> {
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(key)
+        .encrypt(&nonce, plain)
+        .expect("AES-GCM encrypt tidak pernah gagal untuk input sekecil ini");
+    [nonce.as_slice(), &ciphertext].concat()
+}
+
+fn open(key: &Key<Aes256Gcm>, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < NONCE_LEN {
+        return Err("Data terenkripsi terpotong".to_string());
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+    Aes256Gcm::new(key)
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Gagal dekripsi (master key salah atau data rusak)".to_string())
+}
+
+fn encrypt_with(master: &Key<Aes256Gcm>, plain: &str) -> String {
+    let dek = Aes256Gcm::generate_key(OsRng);
+    let wrapped_dek = seal(master, &dek);
+    let ciphertext = seal(&dek, plain.as_bytes());
+    format!("{}{}:{}", PREFIX, B64.encode(wrapped_dek), B64.encode(ciphertext))
+}
+
+fn split(stored: &str) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let body = stored.strip_prefix(PREFIX).ok_or("Bukan secret terenkripsi")?;
+    let (dek, ciphertext) = body.split_once(':').ok_or("Format secret tidak dikenal")?;
+    let decode = |s: &str| B64.decode(s).map_err(|e| format!("Base64 rusak: {}", e));
+    Ok((decode(dek)?, decode(ciphertext)?))
+}
+
+fn decrypt_with(master: &Key<Aes256Gcm>, stored: &str) -> Result<String, String> {
+    // Nilai lama yang belum sempat dienkripsi tetap bisa dibaca
+    if !is_encrypted(stored) {
+        return Ok(stored.to_string());
+    }
+    let (wrapped_dek, ciphertext) = split(stored)?;
+    let dek = open(master, &wrapped_dek)?;
+    let plain = open(Key::<Aes256Gcm>::from_slice(&dek), &ciphertext)?;
+    String::from_utf8(plain).map_err(|_| "Secret bukan UTF-8".to_string())
+}
+
+// Bungkus ulang DEK pakai master key baru, ciphertext isi secret tetap
+fn rewrap(old: &Key<Aes256Gcm>, new: &Key<Aes256Gcm>, stored: &str) -> Result<String, String> {
+    if !is_encrypted(stored) {
+        return Ok(encrypt_with(new, stored));
+    }
+    let (wrapped_dek, ciphertext) = split(stored)?;
+    let dek = open(old, &wrapped_dek)?;
+    Ok(format!("{}{}:{}", PREFIX, B64.encode(seal(new, &dek)), B64.encode(ciphertext)))
+}
+
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(PREFIX)
+}
+
+pub fn encrypt(plain: &str) -> String {
+    encrypt_with(master_key(), plain)
+}
+
+pub fn decrypt(stored: &str) -> Result<String, String> {
+    decrypt_with(master_key(), stored)
+}
+
+// Versi santai untuk kolom nullable: gagal dekripsi dianggap belum diset (dan dicatat di log)
+pub fn reveal(stored: Option<String>) -> Option<String> {
+    let stored = stored.filter(|s| !s.is_empty())?;
+    match decrypt(&stored) {
+        Ok(plain) => Some(plain),
+        Err(e) => {
+            eprintln!("❌ [Crypto] {}", e);
+            None
+        }
+    }
+}
+
+// ==========================================
+// MIGRASI & ROTASI
+// ==========================================
+
+// Enkripsi secret lama yang masih plaintext (dipanggil tiap startup)
+pub async fn encrypt_existing(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+    let mut count = 0;
+    for (table, column) in SECRET_COLUMNS {
+        let rows: Vec<(i64, String)> = sqlx::query_as(&format!(
+            "SELECT id, {column} FROM {table} WHERE {column} IS NOT NULL AND {column} != '' AND {column} NOT LIKE '{PREFIX}%'"
+        ))
+        .fetch_all(pool)
+        .await?;
+
+        for (id, plain) in rows {
+            sqlx::query(&format!("UPDATE {table} SET {column} = ? WHERE id = ?"))
+                .bind(encrypt(&plain))
+                .bind(id)
+                .execute(pool)
+                .await?;
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+// `noty rotate-key`: bungkus ulang semua DEK pakai master key baru.
+// Master key baru diambil dari NOTY_NEW_MASTER_KEY, atau di-generate kalau kosong.
+pub async fn rotate_key(pool: &SqlitePool) -> Result<(), Box<dyn std::error::Error>> {
+    let old = *master_key();
+    let new = match env::var("NOTY_NEW_MASTER_KEY") {
+        Ok(encoded) => parse_key(&encoded)?,
+        Err(_) => Aes256Gcm::generate_key(OsRng),
+    };
+
+    // Key baru ditulis dulu ke file sementara: kalau proses mati di tengah jalan,
+    // key lama & baru dua-duanya masih ada
+    let from_env = env::var("NOTY_MASTER_KEY").is_ok();
+    let path = key_file_path();
+    let staged = path.with_extension("key.new");
+    if !from_env {
+        write_key_file(&staged, &new)?;
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut count = 0;
+    for (table, column) in SECRET_COLUMNS {
+        let rows: Vec<(i64, String)> = sqlx::query_as(&format!(
+            "SELECT id, {column} FROM {table} WHERE {column} IS NOT NULL AND {column} != ''"
+        ))
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (id, stored) in rows {
+            let rewrapped = rewrap(&old, &new, &stored).map_err(|e| format!("{}.{} id={}: {}", table, column, id, e))?;
+            sqlx::query(&format!("UPDATE {table} SET {column} = ? WHERE id = ?"))
+                .bind(rewrapped)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+            count += 1;
+        }
+    }
+    tx.commit().await?;
+
+    println!("🔁 {} secret dibungkus ulang dengan master key baru.", count);
+
+    if from_env {
+        println!("⚠️ Master key dibaca dari NOTY_MASTER_KEY. Ganti nilainya dengan key baru ini:");
+        println!("NOTY_MASTER_KEY={}", B64.encode(new));
+    } else {
+        // Simpan key lama sebagai cadangan, lalu pasang key baru
+        fs::rename(&path, path.with_extension("key.old"))?;
+        fs::rename(&staged, &path)?;
+        println!("🔑 Master key baru disimpan di {} (key lama: {})", path.display(), path.with_extension("key.old").display());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> Key<Aes256Gcm> {
+        Aes256Gcm::generate_key(OsRng)
+    }
+
+    #[test]
+    fn round_trip() {
+        let master = key();
+        let stored = encrypt_with(&master, "ghp_rahasia");
+
+        assert!(is_encrypted(&stored));
+        assert!(!stored.contains("ghp_rahasia"));
+        assert_eq!(decrypt_with(&master, &stored).unwrap(), "ghp_rahasia");
+    }
+
+    #[test]
+    fn same_secret_encrypts_differently() {
+        let master = key();
+        assert_ne!(encrypt_with(&master, "token"), encrypt_with(&master, "token"));
+    }
+
+    #[test]
+    fn wrong_master_key_fails() {
+        let stored = encrypt_with(&key(), "token");
+        assert!(decrypt_with(&key(), &stored).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails() {
+        let master = key();
+        let stored = encrypt_with(&master, "token");
+        let (head, body) = stored.rsplit_once(':').unwrap();
+        let mut bytes = B64.decode(body).unwrap();
+        *bytes.last_mut().unwrap() ^= 1;
+        let tampered = format!("{}:{}", head, B64.encode(bytes));
+
+        assert!(decrypt_with(&master, &tampered).is_err());
+        assert!(decrypt_with(&master, "enc:v1:AAAA:BBBB").is_err());
+    }
+
+    #[test]
+    fn legacy_plaintext_is_readable() {
+        assert_eq!(decrypt_with(&key(), "plain-token").unwrap(), "plain-token");
+    }
+
+    #[test]
+    fn rotation_rewraps_only_the_data_key() {
+        let (old, new) = (key(), key());
+        let stored = encrypt_with(&old, "token");
+
+        let rotated = rewrap(&old, &new, &stored).unwrap();
+
+        assert_eq!(decrypt_with(&new, &rotated).unwrap(), "token");
+        assert!(decrypt_with(&old, &rotated).is_err());
+        // Ciphertext isi secret tetap, yang berubah cuma DEK terbungkus
+        assert_eq!(stored.rsplit_once(':').unwrap().1, rotated.rsplit_once(':').unwrap().1);
+    }
+
+    #[test]
+    fn rotation_encrypts_legacy_plaintext_and_rejects_wrong_old_key() {
+        let (old, new) = (key(), key());
+
+        let rotated = rewrap(&old, &new, "plain-token").unwrap();
+        assert!(is_encrypted(&rotated));
+        assert_eq!(decrypt_with(&new, &rotated).unwrap(), "plain-token");
+
+        let stored = encrypt_with(&key(), "token");
+        assert!(rewrap(&old, &new, &stored).is_err());
+    }
+
+    #[test]
+    fn parse_key_checks_length() {
+        assert!(parse_key(&B64.encode([7u8; 32])).is_ok());
+        assert!(parse_key(&B64.encode([7u8; 16])).is_err());
+        assert!(parse_key("bukan base64!").is_err());
+    }
+}
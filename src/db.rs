@@ -0,0 +1,53 @@
+use sqlx::SqlitePool;
+
+// Versi skema database, disimpan di PRAGMA user_version.
+// Naikkan setiap ada tabel/kolom baru; restore menolak backup dari versi yang lebih baru.
+pub const SCHEMA_VERSION: i64 = 8;
+
+pub async fn schema_version(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar("PRAGMA user_version").fetch_one(pool).await
+}
+
+// Dipanggil setelah semua migrasi di startup selesai
+pub async fn set_schema_version(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("PRAGMA user_version = {}", SCHEMA_VERSION)).execute(pool).await?;
+    Ok(())
+}
+
+pub async fn has_column(pool: &SqlitePool, table: &str, column: &str) -> Result<bool, sqlx::Error> {
+    let count: i64 = sqlx::query_scalar(
+        &format!("SELECT COUNT(*) FROM pragma_table_info('{}') WHERE name = ?", table)
+    )
+    .bind(column)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
+// SQLite belum punya "ADD COLUMN IF NOT EXISTS", jadi cek dulu lewat pragma
+pub async fn add_column_if_missing(
+    pool: &SqlitePool,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), sqlx::Error> {
+    if !has_column(pool, table, column).await? {
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(pool)
+            .await?;
+    }
+
+    Ok(())
+}
+
+// Database in-memory untuk unit test. Satu koneksi saja: tiap koneksi `sqlite::memory:`
+// punya database sendiri, jadi pool yang lebih besar akan melihat tabel yang berbeda.
+#[cfg(test)]
+pub async fn test_pool() -> SqlitePool {
+    sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .expect("gagal membuka sqlite in-memory")
+}
@@ -0,0 +1,114 @@
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use serde_json::json;
+
+// Error standar semua endpoint API. Body-nya selalu:
+//   {"error": {"code": "not_found", "message": "Log #12 tidak ditemukan"}}
+// `code` stabil (buat dicek UI/script), `message` boleh berubah (buat dibaca manusia).
+// Error validasi menambah daftar `fields`: [{"field": "entry_date", "message": "..."}]
+#[derive(Debug)]
+pub struct AppError {
+    pub status: StatusCode,
+    pub code: &'static str,
+    pub message: String,
+    pub fields: Vec<FieldError>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &'static str, message: impl Into<String>) -> Self {
+        FieldError { field, message: message.into() }
+    }
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Into<String>) -> Self {
+        AppError { status, code, message: message.into(), fields: Vec::new() }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "unauthorized", message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "forbidden", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, "conflict", message)
+    }
+
+    // Beberapa field sekaligus salah; message-nya ringkasan semua field
+    pub fn validation(fields: Vec<FieldError>) -> Self {
+        let summary = fields.iter()
+            .map(|f| format!("{}: {}", f.field, f.message))
+            .collect::<Vec<_>>()
+            .join("; ");
+        let mut error = Self::new(StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", format!("Input tidak valid ({})", summary));
+        error.fields = fields;
+        error
+    }
+
+    // Service luar (AI, GitHub, dll) gagal atau jawabannya gak bisa dipakai
+    pub fn upstream(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_GATEWAY, "upstream_error", message)
+    }
+
+    // Detail error internal cukup masuk log server, client dapat pesan umum
+    pub fn internal(context: &str, detail: impl std::fmt::Display) -> Self {
+        eprintln!("❌ {}: {}", context, detail);
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "internal_error", "Terjadi kesalahan di server")
+    }
+
+    // Ganti code bawaan dengan yang lebih spesifik, misal "ai_not_configured"
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = code;
+        self
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        Self::internal("Error database", e).with_code("database_error")
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut body = json!({ "error": { "code": self.code, "message": self.message } });
+        if !self.fields.is_empty() {
+            body["error"]["fields"] = json!(self.fields);
+        }
+        (self.status, Json(body)).into_response()
+    }
+}
+
+// Pengganti axum::Json untuk body request: JSON yang rusak/kurang field juga dijawab format AppError
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(AppError))]
+pub struct JsonBody<T>(pub T);
+
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        Self::new(rejection.status(), "invalid_body", rejection.body_text())
+    }
+}
@@ -0,0 +1,421 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError};
+use crate::logbook::parse_tags;
+
+// Naik kalau struktur dump JSON berubah (dipakai juga oleh import)
+// v2: tambah categories, templates, attachments (metadata) & links
+pub const DUMP_VERSION: u32 = 2;
+
+// Data yang sengaja tidak ada di dump JSON, ikut ditulis di field `omitted` supaya jelas
+const OMITTED: &[(&str, &str)] = &[
+    ("revisions", "Noty tidak menyimpan riwayat revisi; isi log tidak bisa diedit setelah dibuat"),
+    ("attachment_files", "Hanya metadata lampiran (nama, tipe, ukuran, sha256); isi file ada di folder attachments"),
+    ("secrets", "API key AI & integrasi tidak pernah diekspor"),
+];
+
+const FORMATS: &[&str] = &["markdown", "json", "csv"];
+
+const MONTHS: &[&str] = &[
+    "Januari", "Februari", "Maret", "April", "Mei", "Juni",
+    "Juli", "Agustus", "September", "Oktober", "November", "Desember",
+];
+const WEEKDAYS: &[&str] = &["Senin", "Selasa", "Rabu", "Kamis", "Jumat", "Sabtu", "Minggu"];
+
+// GET /api/export?format=markdown&from=2024-01-01&to=2024-12-31&tags=kerja,ide
+#[derive(Deserialize)]
+pub struct ExportQuery {
+    pub format: Option<String>,       // markdown (default), json, csv
+    pub from: Option<String>,         // YYYY-MM-DD, inklusif
+    pub to: Option<String>,           // YYYY-MM-DD, inklusif
+    pub tags: Option<String>,         // Dipisah koma; entry cukup punya salah satu
+    pub include_deleted: Option<bool>, // Ikutkan isi trash (hanya berguna untuk json)
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ExportEntry {
+    pub id: i64,
+    pub content: String,
+    pub entry_date: String,
+    pub entry_time: String,
+    pub created_at: String,
+    #[sqlx(rename = "tags")]
+    #[serde(skip)]
+    pub tags_json: Option<String>,
+    #[sqlx(skip)]
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+    pub mood: Option<String>,
+    pub source: Option<String>,
+    pub is_deleted: bool,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ExportPersona {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub is_active: bool,
+    pub is_custom: bool,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ExportCategory {
+    pub id: i64,
+    pub name: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub parent_id: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ExportTemplate {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub body: String,
+    #[sqlx(rename = "default_tags")]
+    #[serde(skip)]
+    pub default_tags_json: Option<String>,
+    #[sqlx(skip)]
+    pub default_tags: Vec<String>,
+    pub default_category: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ExportAttachment {
+    pub id: i64,
+    pub log_entry_id: i64,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub created_at: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct ExportLink {
+    pub source_id: i64,
+    pub target_id: i64,
+}
+
+struct Filter {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    tags: Vec<String>,
+    include_deleted: bool,
+}
+
+fn parse_query(query: ExportQuery) -> AppResult<(String, Filter)> {
+    let mut errors = Vec::new();
+
+    let format = query.format.unwrap_or_else(|| "markdown".to_string()).trim().to_lowercase();
+    let format = if format == "md" { "markdown".to_string() } else { format };
+    if !FORMATS.contains(&format.as_str()) {
+        errors.push(FieldError::new("format", format!("Format '{}' tidak dikenal, pilih dari: {}", format, FORMATS.join(", "))));
+    }
+
+    let mut parse_date = |field: &'static str, raw: Option<String>| {
+        let raw = raw.filter(|r| !r.trim().is_empty())?;
+        match NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                errors.push(FieldError::new(field, format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", raw)));
+                None
+            }
+        }
+    };
+    let from = parse_date("from", query.from);
+    let to = parse_date("to", query.to);
+
+    if let (Some(from), Some(to)) = (from, to) {
+        if from > to {
+            errors.push(FieldError::new("to", "Tanggal akhir tidak boleh sebelum tanggal awal"));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+
+    let tags = query.tags.unwrap_or_default()
+        .split(',')
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect();
+
+    Ok((format, Filter { from, to, tags, include_deleted: query.include_deleted.unwrap_or(false) }))
+}
+
+async fn fetch_entries(pool: &SqlitePool, user_id: i64, filter: &Filter) -> Result<Vec<ExportEntry>, sqlx::Error> {
+    let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        "SELECT id, content, entry_date, entry_time, CAST(created_at AS TEXT) as created_at, tags, category, mood, source,
+                COALESCE(is_deleted, FALSE) as is_deleted
+         FROM log_entries WHERE user_id = "
+    );
+    qb.push_bind(user_id);
+
+    if !filter.include_deleted {
+        qb.push(" AND is_deleted = FALSE");
+    }
+    if let Some(from) = filter.from {
+        qb.push(" AND entry_date >= ").push_bind(from.format("%Y-%m-%d").to_string());
+    }
+    if let Some(to) = filter.to {
+        qb.push(" AND entry_date <= ").push_bind(to.format("%Y-%m-%d").to_string());
+    }
+    if !filter.tags.is_empty() {
+        qb.push(" AND EXISTS (SELECT 1 FROM json_each(log_entries.tags) WHERE json_each.value IN (");
+        let mut values = qb.separated(", ");
+        for tag in &filter.tags {
+            values.push_bind(tag.clone());
+        }
+        qb.push("))");
+    }
+
+    // Urutan kronologis, cocok untuk dibaca sebagai jurnal
+    qb.push(" ORDER BY entry_date ASC, entry_time ASC, id ASC");
+
+    let mut entries = qb.build_query_as::<ExportEntry>().fetch_all(pool).await?;
+    for entry in &mut entries {
+        entry.tags = parse_tags(entry.tags_json.as_deref());
+    }
+    Ok(entries)
+}
+
+// ==========================================
+// HANDLER
+// ==========================================
+
+pub async fn export_logs(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<ExportQuery>,
+) -> AppResult<Response> {
+    let (format, filter) = parse_query(query)?;
+    let entries = fetch_entries(&pool, user.id, &filter).await?;
+    let today = chrono::Local::now().format("%Y-%m-%d");
+
+    let (body, content_type, extension) = match format.as_str() {
+        "json" => (to_json(&pool, &user, &filter, entries).await?, "application/json", "json"),
+        "csv" => (to_csv(&entries)?, "text/csv; charset=utf-8", "csv"),
+        _ => (to_markdown(&user, &filter, &entries), "text/markdown; charset=utf-8", "md"),
+    };
+
+    let disposition = format!("attachment; filename=\"noty-export-{}.{}\"", today, extension);
+    Ok(([(header::CONTENT_TYPE, content_type.to_string()), (header::CONTENT_DISPOSITION, disposition)], body).into_response())
+}
+
+// ==========================================
+// FORMAT: MARKDOWN (jurnal per tahun/bulan/hari)
+// ==========================================
+
+fn describe_range(filter: &Filter) -> Option<String> {
+    let fmt = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
+    match (filter.from, filter.to) {
+        (Some(from), Some(to)) => Some(format!("{} s/d {}", fmt(from), fmt(to))),
+        (Some(from), None) => Some(format!("sejak {}", fmt(from))),
+        (None, Some(to)) => Some(format!("sampai {}", fmt(to))),
+        (None, None) => None,
+    }
+}
+
+fn to_markdown(user: &AuthUser, filter: &Filter, entries: &[ExportEntry]) -> String {
+    let mut out = format!("# Logbook {}\n\n", user.username);
+    if let Some(range) = describe_range(filter) {
+        out.push_str(&format!("- Periode: {}\n", range));
+    }
+    if !filter.tags.is_empty() {
+        out.push_str(&format!("- Tag: {}\n", filter.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" ")));
+    }
+    out.push_str(&format!("- Jumlah catatan: {}\n", entries.len()));
+
+    // tahun -> bulan -> tanggal -> entries (BTreeMap supaya urut)
+    let mut grouped: BTreeMap<i32, BTreeMap<u32, BTreeMap<NaiveDate, Vec<&ExportEntry>>>> = BTreeMap::new();
+    let mut undated = Vec::new();
+    for entry in entries {
+        match NaiveDate::parse_from_str(&entry.entry_date, "%Y-%m-%d") {
+            Ok(date) => grouped.entry(date.year()).or_default()
+                .entry(date.month()).or_default()
+                .entry(date).or_default()
+                .push(entry),
+            // Data lama sebelum validasi tanggal, tetap ikut diekspor
+            Err(_) => undated.push(entry),
+        }
+    }
+
+    for (year, months) in &grouped {
+        out.push_str(&format!("\n## {}\n", year));
+        for (month, days) in months {
+            out.push_str(&format!("\n### {} {}\n", MONTHS[*month as usize - 1], year));
+            for (date, day_entries) in days {
+                out.push_str(&format!(
+                    "\n#### {}, {} {} {}\n",
+                    WEEKDAYS[date.weekday().num_days_from_monday() as usize],
+                    date.day(),
+                    MONTHS[*month as usize - 1],
+                    year
+                ));
+                for entry in day_entries {
+                    push_entry(&mut out, entry);
+                }
+            }
+        }
+    }
+
+    if !undated.is_empty() {
+        out.push_str("\n## Tanpa tanggal valid\n");
+        for entry in undated {
+            push_entry(&mut out, entry);
+        }
+    }
+
+    out
+}
+
+fn push_entry(out: &mut String, entry: &ExportEntry) {
+    let time = entry.entry_time.get(..5).unwrap_or(&entry.entry_time);
+    let mut meta = vec![format!("**{}**", time)];
+    meta.extend(entry.category.clone());
+    meta.extend(entry.mood.clone());
+    meta.extend(entry.source.clone());
+    if entry.is_deleted {
+        meta.push("(dihapus)".to_string());
+    }
+
+    out.push_str(&format!("\n{}\n", meta.join(" · ")));
+    if !entry.tags.is_empty() {
+        out.push_str(&format!("\n{}\n", entry.tags.iter().map(|t| format!("#{}", t.replace(' ', "-"))).collect::<Vec<_>>().join(" ")));
+    }
+    out.push_str(&format!("\n{}\n", entry.content.trim_end()));
+}
+
+// ==========================================
+// FORMAT: CSV (satu baris per entry)
+// ==========================================
+
+fn to_csv(entries: &[ExportEntry]) -> AppResult<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let write_err = |e: csv::Error| AppError::internal("Gagal menulis CSV", e);
+
+    writer.write_record(["id", "entry_date", "entry_time", "created_at", "category", "mood", "source", "tags", "content"])
+        .map_err(write_err)?;
+    for entry in entries {
+        writer.write_record([
+            entry.id.to_string(),
+            entry.entry_date.clone(),
+            entry.entry_time.clone(),
+            entry.created_at.clone(),
+            entry.category.clone().unwrap_or_default(),
+            entry.mood.clone().unwrap_or_default(),
+            entry.source.clone().unwrap_or_default(),
+            entry.tags.join(", "),
+            entry.content.clone(),
+        ])
+        .map_err(write_err)?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| AppError::internal("Gagal menulis CSV", e))?;
+    String::from_utf8(bytes).map_err(|e| AppError::internal("Gagal menulis CSV", e))
+}
+
+// ==========================================
+// FORMAT: JSON (dump data logbook, bisa di-import lagi; yang tidak ikut ada di OMITTED)
+// ==========================================
+
+async fn to_json(pool: &SqlitePool, user: &AuthUser, filter: &Filter, entries: Vec<ExportEntry>) -> AppResult<String> {
+    let personas = sqlx::query_as::<_, ExportPersona>(
+        "SELECT id, name, description, system_prompt, COALESCE(is_active, FALSE) as is_active, COALESCE(is_custom, FALSE) as is_custom
+         FROM ai_personas WHERE user_id = ? ORDER BY id ASC"
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?;
+
+    let categories = sqlx::query_as::<_, ExportCategory>(
+        "SELECT id, name, color, icon, parent_id, CAST(created_at AS TEXT) as created_at
+         FROM categories WHERE user_id = ? ORDER BY id ASC"
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut templates = sqlx::query_as::<_, ExportTemplate>(
+        "SELECT id, name, description, body, default_tags, default_category,
+                CAST(created_at AS TEXT) as created_at, CAST(updated_at AS TEXT) as updated_at
+         FROM templates WHERE user_id = ? ORDER BY id ASC"
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?;
+    for template in &mut templates {
+        template.default_tags = parse_tags(template.default_tags_json.as_deref());
+    }
+
+    // Lampiran & link hanya untuk entry yang ikut diekspor (filter tanggal/tag/trash berlaku)
+    let exported: HashSet<i64> = entries.iter().map(|e| e.id).collect();
+    let attachments: Vec<ExportAttachment> = sqlx::query_as::<_, ExportAttachment>(
+        "SELECT id, log_entry_id, file_name, mime_type, size_bytes, sha256, CAST(created_at AS TEXT) as created_at
+         FROM attachments WHERE user_id = ? ORDER BY id ASC"
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .filter(|a| exported.contains(&a.log_entry_id))
+    .collect();
+    let links: Vec<ExportLink> = sqlx::query_as::<_, ExportLink>(
+        "SELECT source_id, target_id FROM entry_links WHERE user_id = ? ORDER BY source_id, target_id"
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .filter(|l| exported.contains(&l.source_id) && exported.contains(&l.target_id))
+    .collect();
+
+    // Ringkasan tag: nama -> jumlah pemakaian
+    let mut tags: BTreeMap<&str, usize> = BTreeMap::new();
+    for tag in entries.iter().flat_map(|e| &e.tags) {
+        *tags.entry(tag).or_default() += 1;
+    }
+    let tags: Vec<_> = tags.into_iter().map(|(name, count)| json!({ "name": name, "count": count })).collect();
+    let omitted: Vec<_> = OMITTED.iter().map(|(name, reason)| json!({ "name": name, "reason": reason })).collect();
+
+    let dump = json!({
+        "noty_export": DUMP_VERSION,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "user": user.username,
+        "filter": {
+            "from": filter.from.map(|d| d.format("%Y-%m-%d").to_string()),
+            "to": filter.to.map(|d| d.format("%Y-%m-%d").to_string()),
+            "tags": filter.tags,
+            "include_deleted": filter.include_deleted,
+        },
+        "entries": entries,
+        "personas": personas,
+        "tags": tags,
+        "categories": categories,
+        "templates": templates,
+        "attachments": attachments,
+        "links": links,
+        "omitted": omitted,
+    });
+
+    serde_json::to_string_pretty(&dump).map_err(|e| AppError::internal("Gagal menulis JSON", e))
+}
@@ -1,121 +1,115 @@
+use async_trait::async_trait;
 use sqlx::SqlitePool;
 use std::time::Duration;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use reqwest::header;
 
-#[derive(Deserialize, Debug)]
+use crate::integrations::{ExternalItem, Integration, IntegrationConfig, PollResult};
+use crate::logbook::CreateLogRequest;
+
+#[derive(Serialize, Deserialize, Debug)]
 struct GitHubCommit {
     sha: String,
     commit: CommitDetails,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct CommitDetails {
     message: String,
     author: AuthorDetails,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct AuthorDetails {
     date: String,
 }
 
-pub async fn start_github_polling(pool: SqlitePool) {
-    let client = reqwest::Client::new();
-    println!("👀 GitHub Watcher Service Started (Debug Mode)...");
-
-    loop {
-        println!("🔄 [Sync] Mengecek konfigurasi integrasi di DB...");
-
-        // 1. Ambil config dari DB
-        let integration = sqlx::query!(
-            "SELECT config_json, api_key FROM integrations WHERE service_name = 'github' AND is_active = TRUE LIMIT 1"
-        )
-        .fetch_optional(&pool)
-        .await
-        .unwrap_or(None);
-
-        if let Some(config) = integration {
-            let repo_name = config.config_json.unwrap_or_default();
-            let token = config.api_key.unwrap_or_default();
-
-            println!("✅ [Sync] Config ditemukan: Repo='{}', TokenSet={}", repo_name, !token.is_empty());
-
-            if !repo_name.is_empty() {
-                // 2. Tembak GitHub API
-                let url = format!("https://api.github.com/repos/{}/commits?per_page=5", repo_name);
-                println!("🚀 [Sync] Mengirim request ke: {}", url);
-                
-                let mut headers = header::HeaderMap::new();
-                headers.insert("User-Agent", header::HeaderValue::from_static("Noty-Logbook"));
-                if !token.is_empty() {
-                    let auth_str = format!("Bearer {}", token);
-                    if let Ok(val) = header::HeaderValue::from_str(&auth_str) {
-                        headers.insert("Authorization", val);
-                    }
-                }
-
-                match client.get(&url).headers(headers).send().await {
-                    Ok(res) => {
-                        let status = res.status();
-                        println!("📡 [Sync] Response Status: {}", status);
-
-                        if status.is_success() {
-                            match res.json::<Vec<GitHubCommit>>().await {
-                                Ok(commits) => {
-                                    println!("📦 [Sync] Berhasil fetch {} commits.", commits.len());
-                                    for commit in commits {
-                                        let sha_pattern = format!("%{}%", commit.sha);
-                                        
-                                        // Cek Duplikat
-                                        let count: i32 = sqlx::query_scalar(
-                                            "SELECT count(*) FROM log_entries WHERE source = 'GitHub' AND content LIKE ?"
-                                        )
-                                        .bind(&sha_pattern)
-                                        .fetch_one(&pool)
-                                        .await
-                                        .unwrap_or(0);
-
-                                        if count == 0 {
-                                            println!("✨ [Sync] Menambahkan Commit Baru ke DB: {}", commit.commit.message);
-                                            
-                                            let content = format!("**GitHub Update:** {}\n\n*Commit ID: {}*", commit.commit.message, commit.sha);
-                                            
-                                            // Auto-Insert
-                                            let insert_result = sqlx::query!(
-                                                "INSERT INTO log_entries (content, entry_date, entry_time, tags, category, source, mood) 
-                                                 VALUES (?, date('now'), time('now'), ?, 'Development', 'GitHub', 'Neutral')",
-                                                content,
-                                                "[\"coding\", \"github\"]"
-                                            )
-                                            .execute(&pool)
-                                            .await;
-
-                                            if let Err(e) = insert_result {
-                                                println!("❌ [Sync] Gagal insert DB: {}", e);
-                                            }
-                                        } else {
-                                            // println!("zzz [Sync] Commit {} sudah ada.", &commit.sha[0..7]);
-                                        }
-                                    }
-                                },
-                                Err(e) => println!("❌ [Sync] Gagal parsing JSON GitHub: {}", e),
-                            }
-                        } else {
-                            // Print body kalau error (misal 404 Not Found atau 401 Unauthorized)
-                            let body = res.text().await.unwrap_or_default();
-                            println!("⚠️ [Sync] GitHub Error Body: {}", body);
-                        }
-                    },
-                    Err(e) => println!("❌ [Sync] Gagal koneksi HTTP: {}", e),
-                }
+// Config: {"repo_name": "owner/repo"}, token di kolom api_key
+pub struct GitHubIntegration;
+
+fn repo_name(config: &Value) -> String {
+    config.get("repo_name").and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+#[async_trait]
+impl Integration for GitHubIntegration {
+    fn service_name(&self) -> &'static str {
+        "github"
+    }
+
+    // 10 detik biar gak bosen nunggu (tanpa token kena rate limit 60 req/jam, pasang token)
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn configure(&self, config: Value) -> Result<Value, String> {
+        Ok(json!({ "repo_name": repo_name(&config).trim() }))
+    }
+
+    fn validate(&self, config: &IntegrationConfig) -> Result<(), String> {
+        let repo = repo_name(&config.config);
+        if repo.split('/').filter(|p| !p.is_empty()).count() != 2 {
+            return Err(format!("repo_name harus berformat 'owner/repo', dapat '{}'", repo));
+        }
+        Ok(())
+    }
+
+    async fn poll(&self, _pool: &SqlitePool, config: &IntegrationConfig) -> Result<PollResult, String> {
+        let client = reqwest::Client::new();
+        let url = format!("https://api.github.com/repos/{}/commits?per_page=5", repo_name(&config.config));
+
+        let mut headers = header::HeaderMap::new();
+        headers.insert("User-Agent", header::HeaderValue::from_static("Noty-Logbook"));
+        if !config.api_key.is_empty() {
+            let auth_str = format!("Bearer {}", config.api_key);
+            if let Ok(val) = header::HeaderValue::from_str(&auth_str) {
+                headers.insert("Authorization", val);
             }
-        } else {
-            println!("zzz [Sync] Tidak ada integrasi GitHub yang AKTIF di database.");
         }
 
-        // PERCEPAT JADI 10 DETIK (Biar gak bosen nunggu)
-        println!("⏳ [Sync] Tidur 10 detik...");
-        tokio::time::sleep(Duration::from_secs(10)).await;
+        let res = client.get(&url).headers(headers).send().await
+            .map_err(|e| format!("Gagal koneksi HTTP: {}", e))?;
+
+        let status = res.status();
+        if !status.is_success() {
+            // Body error (misal 404 Not Found atau 401 Unauthorized) ikut disimpan biar gampang debug
+            let body = res.text().await.unwrap_or_default();
+            return Err(format!("GitHub Error {}: {}", status, body));
+        }
+
+        let commits = res.json::<Vec<GitHubCommit>>().await
+            .map_err(|e| format!("Gagal parsing JSON GitHub: {}", e))?;
+
+        let items = commits.into_iter()
+            .map(|commit| ExternalItem {
+                external_id: commit.sha.clone(),
+                payload: serde_json::to_value(&commit).unwrap_or(Value::Null),
+                state: None,
+            })
+            .collect();
+
+        // Duplikat dicek lewat external_refs, jadi gak perlu cursor
+        Ok(PollResult { items, next_state: None })
+    }
+
+    fn to_log_entry(&self, item: &ExternalItem) -> Option<CreateLogRequest> {
+        let commit: GitHubCommit = serde_json::from_value(item.payload.clone()).ok()?;
+
+        // Pakai waktu commit; kalau formatnya aneh, anggap sekarang
+        let committed_at = chrono::DateTime::parse_from_rfc3339(&commit.commit.author.date)
+            .map(|d| d.with_timezone(&chrono::Utc))
+            .unwrap_or_else(|_| chrono::Utc::now());
+
+        Some(CreateLogRequest {
+            content: format!("**GitHub Update:** {}\n\n*Commit ID: {}*", commit.commit.message, commit.sha),
+            entry_date: committed_at.format("%Y-%m-%d").to_string(),
+            entry_time: committed_at.format("%H:%M:%S").to_string(),
+            tags: vec!["coding".to_string(), "github".to_string()],
+            category: Some("Development".to_string()),
+            mood: Some("Neutral".to_string()),
+            source: Some("GitHub".to_string()),
+            template_id: None,
+        })
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,712 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeMap;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError, JsonBody};
+
+// daily_entries  : minimal N log per hari kerja
+// weekly_entries : minimal N log per minggu (Senin - Minggu)
+// weekly_words   : minimal N kata per minggu
+const KINDS: &[&str] = &["daily_entries", "weekly_entries", "weekly_words"];
+
+// Streak dihitung dari riwayat ~13 bulan terakhir
+const HISTORY_DAYS: i64 = 400;
+const CHECK_EVERY: std::time::Duration = std::time::Duration::from_secs(60);
+const DEFAULT_WORKDAYS: &str = "1,2,3,4,5"; // Senin = 1 ... Minggu = 7
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS goals (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            kind TEXT NOT NULL,                -- daily_entries, weekly_entries, weekly_words
+            target INTEGER NOT NULL,
+            manual_only BOOLEAN DEFAULT TRUE,  -- Hanya hitung log yang ditulis sendiri (source = Manual)
+            is_active BOOLEAN DEFAULT TRUE,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );"
+    ).execute(pool).await?;
+
+    // Hari kerja & jam nudge per user (baris dibuat saat pertama kali disimpan)
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS goal_schedules (
+            user_id INTEGER PRIMARY KEY REFERENCES users(id),
+            workdays TEXT DEFAULT '1,2,3,4,5', -- ISO weekday, Senin = 1
+            nudge_time TEXT,                   -- HH:MM waktu lokal, NULL = nudge mati
+            last_nudged_on TEXT                -- YYYY-MM-DD, supaya nudge cuma sekali sehari
+        );"
+    ).execute(pool).await?;
+
+    // Libur tidak memutus streak & tidak dapat nudge
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS goal_holidays (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            date TEXT NOT NULL,
+            name TEXT,
+            UNIQUE(user_id, date)
+        );"
+    ).execute(pool).await?;
+
+    Ok(())
+}
+
+// ==========================================
+// MODEL
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow, Clone)]
+pub struct Goal {
+    pub id: i64,
+    pub kind: String,
+    pub target: i64,
+    pub manual_only: bool,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+const GOAL_COLUMNS: &str = "id, kind, target, COALESCE(manual_only, TRUE) as manual_only,
+    COALESCE(is_active, TRUE) as is_active, CAST(created_at AS TEXT) as created_at";
+
+#[derive(Deserialize)]
+pub struct GoalRequest {
+    pub kind: String,
+    pub target: i64,
+    pub manual_only: Option<bool>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct Schedule {
+    pub workdays: Vec<u32>,
+    pub nudge_time: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ScheduleRequest {
+    pub workdays: Vec<u32>,
+    pub nudge_time: Option<String>, // "17:30", null/kosong = matikan nudge
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Holiday {
+    pub id: i64,
+    pub date: String,
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct HolidayRequest {
+    pub date: String,
+    pub name: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub period_start: String,
+    pub period_end: String,
+    pub current: i64,
+    pub target: i64,
+    pub met: bool,
+    pub percent: f64,
+    pub streak: GoalStreak,
+}
+
+#[derive(Serialize, Default)]
+pub struct GoalStreak {
+    pub unit: &'static str, // day atau week
+    pub current: i64,
+    pub longest: i64,
+}
+
+#[derive(Serialize)]
+pub struct ProgressReport {
+    pub date: String,
+    pub is_workday: bool,
+    pub holiday: Option<String>,
+    pub goals: Vec<GoalProgress>,
+}
+
+// Riwayat harian: jumlah log & kata, dipisah semua sumber vs manual
+#[derive(Default, Clone, Copy)]
+struct DayTotals {
+    entries: i64,
+    words: i64,
+    manual_entries: i64,
+    manual_words: i64,
+}
+
+impl DayTotals {
+    fn value(&self, goal: &Goal) -> i64 {
+        match (goal.kind.as_str(), goal.manual_only) {
+            ("weekly_words", true) => self.manual_words,
+            ("weekly_words", false) => self.words,
+            (_, true) => self.manual_entries,
+            (_, false) => self.entries,
+        }
+    }
+}
+
+// Hari kerja + libur milik satu user
+struct Calendar {
+    workdays: Vec<u32>,
+    holidays: BTreeMap<NaiveDate, Option<String>>,
+}
+
+impl Calendar {
+    fn is_off(&self, date: NaiveDate) -> bool {
+        !self.workdays.contains(&date.weekday().number_from_monday()) || self.holidays.contains_key(&date)
+    }
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn parse_workdays(raw: Option<&str>) -> Vec<u32> {
+    let days: Vec<u32> = raw.unwrap_or(DEFAULT_WORKDAYS)
+        .split(',')
+        .filter_map(|d| d.trim().parse().ok())
+        .filter(|d| (1..=7).contains(d))
+        .collect();
+    if days.is_empty() { vec![1, 2, 3, 4, 5] } else { days }
+}
+
+fn validate_goal(payload: &GoalRequest) -> AppResult<String> {
+    let mut errors = Vec::new();
+    let kind = payload.kind.trim().to_lowercase();
+    if !KINDS.contains(&kind.as_str()) {
+        errors.push(FieldError::new("kind", format!("Pilih salah satu: {}", KINDS.join(", "))));
+    }
+    if !(1..=100_000).contains(&payload.target) {
+        errors.push(FieldError::new("target", "Harus 1-100000"));
+    }
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+    Ok(kind)
+}
+
+async fn find(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<Goal> {
+    sqlx::query_as::<_, Goal>(&format!("SELECT {} FROM goals WHERE id = ? AND user_id = ?", GOAL_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Goal #{} tidak ditemukan", id)))
+}
+
+async fn load_schedule(pool: &SqlitePool, user_id: i64) -> Result<(Vec<u32>, Option<String>), sqlx::Error> {
+    let row: Option<(Option<String>, Option<String>)> = sqlx::query_as("SELECT workdays, nudge_time FROM goal_schedules WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?;
+    let (workdays, nudge_time) = row.unwrap_or_default();
+    Ok((parse_workdays(workdays.as_deref()), nudge_time))
+}
+
+async fn load_calendar(pool: &SqlitePool, user_id: i64) -> Result<Calendar, sqlx::Error> {
+    let (workdays, _) = load_schedule(pool, user_id).await?;
+    let rows: Vec<(String, Option<String>)> = sqlx::query_as("SELECT date, name FROM goal_holidays WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    let holidays = rows.into_iter()
+        .filter_map(|(date, name)| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok().map(|d| (d, name)))
+        .collect();
+    Ok(Calendar { workdays, holidays })
+}
+
+async fn load_history(pool: &SqlitePool, user_id: i64, today: NaiveDate) -> Result<BTreeMap<NaiveDate, DayTotals>, sqlx::Error> {
+    let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+        "SELECT entry_date, content, source FROM log_entries
+         WHERE user_id = ? AND is_deleted = FALSE AND entry_date >= ? AND entry_date <= ?"
+    )
+    .bind(user_id)
+    .bind((today - Duration::days(HISTORY_DAYS)).format("%Y-%m-%d").to_string())
+    .bind(today.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await?;
+
+    let mut history: BTreeMap<NaiveDate, DayTotals> = BTreeMap::new();
+    for (date, content, source) in rows {
+        let Ok(date) = NaiveDate::parse_from_str(&date, "%Y-%m-%d") else { continue };
+        let words = content.split_whitespace().count() as i64;
+        let day = history.entry(date).or_default();
+        day.entries += 1;
+        day.words += words;
+        if source.as_deref().unwrap_or("Manual") == "Manual" {
+            day.manual_entries += 1;
+            day.manual_words += words;
+        }
+    }
+    Ok(history)
+}
+
+// ==========================================
+// PERHITUNGAN PROGRESS & STREAK
+// ==========================================
+
+fn daily_progress(goal: &Goal, history: &BTreeMap<NaiveDate, DayTotals>, calendar: &Calendar, today: NaiveDate) -> GoalProgress {
+    let value = |d: NaiveDate| history.get(&d).map(|t| t.value(goal)).unwrap_or(0);
+    let met = |d: NaiveDate| value(d) >= goal.target;
+
+    // Hari ini masih berjalan: belum tercapai bukan berarti streak putus
+    let mut streak = GoalStreak { unit: "day", ..Default::default() };
+    let mut day = if met(today) { today } else { today - Duration::days(1) };
+    let oldest = today - Duration::days(HISTORY_DAYS);
+    while day >= oldest {
+        if met(day) {
+            streak.current += 1;
+        } else if !calendar.is_off(day) {
+            break;
+        }
+        day -= Duration::days(1);
+    }
+
+    // Libur & akhir pekan dilewati: tidak menambah dan tidak memutus streak
+    let mut run = 0;
+    for day in oldest.iter_days().take_while(|d| *d <= today) {
+        if met(day) {
+            run += 1;
+            streak.longest = streak.longest.max(run);
+        } else if !calendar.is_off(day) && day != today {
+            run = 0;
+        }
+    }
+
+    progress(goal, today, today, value(today), streak)
+}
+
+fn weekly_progress(goal: &Goal, history: &BTreeMap<NaiveDate, DayTotals>, today: NaiveDate) -> GoalProgress {
+    let mut weeks: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    for (date, totals) in history {
+        *weeks.entry(week_start(*date)).or_default() += totals.value(goal);
+    }
+    let this_week = week_start(today);
+    let met = |w: NaiveDate| weeks.get(&w).copied().unwrap_or(0) >= goal.target;
+
+    let mut streak = GoalStreak { unit: "week", ..Default::default() };
+    let mut week = if met(this_week) { this_week } else { this_week - Duration::weeks(1) };
+    while met(week) {
+        streak.current += 1;
+        week -= Duration::weeks(1);
+    }
+
+    let mut run = 0;
+    let mut week = week_start(today - Duration::days(HISTORY_DAYS));
+    while week <= this_week {
+        if met(week) {
+            run += 1;
+            streak.longest = streak.longest.max(run);
+        } else if week != this_week {
+            run = 0;
+        }
+        week += Duration::weeks(1);
+    }
+
+    let current = weeks.get(&this_week).copied().unwrap_or(0);
+    progress(goal, this_week, this_week + Duration::days(6), current, streak)
+}
+
+fn progress(goal: &Goal, start: NaiveDate, end: NaiveDate, current: i64, streak: GoalStreak) -> GoalProgress {
+    GoalProgress {
+        goal: goal.clone(),
+        period_start: start.format("%Y-%m-%d").to_string(),
+        period_end: end.format("%Y-%m-%d").to_string(),
+        current,
+        target: goal.target,
+        met: current >= goal.target,
+        percent: ((current as f64 * 100.0 / goal.target as f64).min(100.0) * 10.0).round() / 10.0,
+        streak,
+    }
+}
+
+async fn compute_progress(pool: &SqlitePool, user_id: i64, today: NaiveDate) -> Result<ProgressReport, sqlx::Error> {
+    let goals = sqlx::query_as::<_, Goal>(&format!(
+        "SELECT {} FROM goals WHERE user_id = ? AND is_active = TRUE ORDER BY id ASC", GOAL_COLUMNS
+    ))
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+    let calendar = load_calendar(pool, user_id).await?;
+    let history = load_history(pool, user_id, today).await?;
+
+    let goals = goals.iter()
+        .map(|goal| match goal.kind.as_str() {
+            "daily_entries" => daily_progress(goal, &history, &calendar, today),
+            _ => weekly_progress(goal, &history, today),
+        })
+        .collect();
+
+    Ok(ProgressReport {
+        date: today.format("%Y-%m-%d").to_string(),
+        is_workday: !calendar.is_off(today),
+        holiday: calendar.holidays.get(&today).map(|name| name.clone().unwrap_or_else(|| "Libur".to_string())),
+        goals,
+    })
+}
+
+// ==========================================
+// API GOAL
+// ==========================================
+
+// GET /api/goals
+pub async fn list_goals(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<Goal>>> {
+    let goals = sqlx::query_as::<_, Goal>(&format!("SELECT {} FROM goals WHERE user_id = ? ORDER BY id ASC", GOAL_COLUMNS))
+        .bind(user.id)
+        .fetch_all(&pool)
+        .await?;
+    Ok(Json(goals))
+}
+
+// POST /api/goals
+pub async fn create_goal(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<GoalRequest>,
+) -> AppResult<(StatusCode, Json<Goal>)> {
+    let kind = validate_goal(&payload)?;
+
+    let id = sqlx::query("INSERT INTO goals (user_id, kind, target, manual_only, is_active) VALUES (?, ?, ?, ?, ?)")
+        .bind(user.id)
+        .bind(kind)
+        .bind(payload.target)
+        .bind(payload.manual_only.unwrap_or(true))
+        .bind(payload.is_active.unwrap_or(true))
+        .execute(&pool)
+        .await?
+        .last_insert_rowid();
+
+    Ok((StatusCode::CREATED, Json(find(&pool, user.id, id).await?)))
+}
+
+// PUT /api/goals/{id}
+pub async fn update_goal(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<GoalRequest>,
+) -> AppResult<Json<Goal>> {
+    let existing = find(&pool, user.id, id).await?;
+    let kind = validate_goal(&payload)?;
+
+    sqlx::query("UPDATE goals SET kind = ?, target = ?, manual_only = ?, is_active = ? WHERE id = ? AND user_id = ?")
+        .bind(kind)
+        .bind(payload.target)
+        .bind(payload.manual_only.unwrap_or(existing.manual_only))
+        .bind(payload.is_active.unwrap_or(existing.is_active))
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+
+    Ok(Json(find(&pool, user.id, id).await?))
+}
+
+// DELETE /api/goals/{id}
+pub async fn delete_goal(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM goals WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Goal #{} tidak ditemukan", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// GET /api/goals/progress -> progress periode berjalan + streak tiap goal aktif
+pub async fn get_progress(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<ProgressReport>> {
+    let today = chrono::Local::now().date_naive();
+    Ok(Json(compute_progress(&pool, user.id, today).await?))
+}
+
+// ==========================================
+// API JADWAL & LIBUR
+// ==========================================
+
+// GET /api/goals/schedule
+pub async fn get_schedule(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Schedule>> {
+    let (workdays, nudge_time) = load_schedule(&pool, user.id).await?;
+    Ok(Json(Schedule { workdays, nudge_time }))
+}
+
+// PUT /api/goals/schedule
+pub async fn update_schedule(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<ScheduleRequest>,
+) -> AppResult<Json<Schedule>> {
+    let mut errors = Vec::new();
+
+    let mut workdays = payload.workdays;
+    workdays.sort_unstable();
+    workdays.dedup();
+    if workdays.is_empty() || workdays.iter().any(|d| !(1..=7).contains(d)) {
+        errors.push(FieldError::new("workdays", "Isi minimal satu hari, 1 (Senin) sampai 7 (Minggu)"));
+    }
+
+    let nudge_time = match payload.nudge_time.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        None => None,
+        Some(raw) => match NaiveTime::parse_from_str(raw, "%H:%M") {
+            Ok(time) => Some(time.format("%H:%M").to_string()),
+            Err(_) => {
+                errors.push(FieldError::new("nudge_time", format!("Format jam harus HH:MM, dapat '{}'", raw)));
+                None
+            }
+        },
+    };
+
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+
+    let joined = workdays.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(",");
+    sqlx::query(
+        "INSERT INTO goal_schedules (user_id, workdays, nudge_time) VALUES (?, ?, ?)
+         ON CONFLICT(user_id) DO UPDATE SET workdays = excluded.workdays, nudge_time = excluded.nudge_time"
+    )
+    .bind(user.id)
+    .bind(joined)
+    .bind(&nudge_time)
+    .execute(&pool)
+    .await?;
+
+    Ok(Json(Schedule { workdays, nudge_time }))
+}
+
+// GET /api/goals/holidays
+pub async fn list_holidays(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<Holiday>>> {
+    let holidays = sqlx::query_as::<_, Holiday>("SELECT id, date, name FROM goal_holidays WHERE user_id = ? ORDER BY date ASC")
+        .bind(user.id)
+        .fetch_all(&pool)
+        .await?;
+    Ok(Json(holidays))
+}
+
+// POST /api/goals/holidays
+pub async fn create_holiday(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<HolidayRequest>,
+) -> AppResult<(StatusCode, Json<Holiday>)> {
+    let date = NaiveDate::parse_from_str(payload.date.trim(), "%Y-%m-%d").map_err(|_| AppError::validation(vec![
+        FieldError::new("date", format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", payload.date)),
+    ]))?;
+    let name = payload.name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+    let result = sqlx::query("INSERT INTO goal_holidays (user_id, date, name) VALUES (?, ?, ?)")
+        .bind(user.id)
+        .bind(date.format("%Y-%m-%d").to_string())
+        .bind(&name)
+        .execute(&pool)
+        .await;
+
+    let id = match result {
+        Ok(r) => r.last_insert_rowid(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return Err(AppError::conflict(format!("Tanggal {} sudah ditandai libur", date)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((StatusCode::CREATED, Json(Holiday { id, date: date.format("%Y-%m-%d").to_string(), name })))
+}
+
+// DELETE /api/goals/holidays/{id}
+pub async fn delete_holiday(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM goal_holidays WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Libur #{} tidak ditemukan", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ==========================================
+// NUDGE AKHIR HARI
+// ==========================================
+
+// Sekali sehari setelah jam nudge: kalau goal harian belum tercapai (atau goal mingguan
+// belum tercapai di hari kerja terakhir minggu itu), kirim pengingat ke inbox.
+pub async fn start_nudges(pool: SqlitePool) {
+    loop {
+        if let Err(e) = send_nudges(&pool).await {
+            eprintln!("❌ Nudge goal gagal: {}", e);
+        }
+        tokio::time::sleep(CHECK_EVERY).await;
+    }
+}
+
+async fn send_nudges(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let now = chrono::Local::now().naive_local();
+    let today = now.date();
+    let today_text = today.format("%Y-%m-%d").to_string();
+
+    let due: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT user_id, nudge_time FROM goal_schedules
+         WHERE nudge_time IS NOT NULL AND nudge_time <= ? AND (last_nudged_on IS NULL OR last_nudged_on < ?)"
+    )
+    .bind(now.format("%H:%M").to_string())
+    .bind(&today_text)
+    .fetch_all(pool)
+    .await?;
+
+    for (user_id, _) in due {
+        // Tandai dulu supaya error di bawah tidak bikin nudge terkirim berulang
+        sqlx::query("UPDATE goal_schedules SET last_nudged_on = ? WHERE user_id = ?")
+            .bind(&today_text)
+            .bind(user_id)
+            .execute(pool)
+            .await?;
+
+        let calendar = load_calendar(pool, user_id).await?;
+        if calendar.is_off(today) {
+            continue;
+        }
+        let last_workday = calendar.workdays.iter().max().copied() == Some(today.weekday().number_from_monday());
+
+        let report = compute_progress(pool, user_id, today).await?;
+        let missing: Vec<String> = report.goals.iter()
+            .filter(|p| !p.met && (p.goal.kind == "daily_entries" || last_workday))
+            .map(|p| format!("{}: {}/{}", describe(&p.goal), p.current, p.target))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+
+        let streak = report.goals.iter().filter(|p| p.streak.unit == "day").map(|p| p.streak.current).max().unwrap_or(0);
+        let title = if streak > 0 {
+            format!("✍️ Jangan putus streak {} hari!", streak)
+        } else {
+            "✍️ Belum nulis log hari ini".to_string()
+        };
+        crate::reminders::push_notification(pool, user_id, &title, Some(&missing.join("\n"))).await?;
+    }
+
+    Ok(())
+}
+
+fn describe(goal: &Goal) -> &'static str {
+    match goal.kind.as_str() {
+        "daily_entries" => "Log hari ini",
+        "weekly_entries" => "Log minggu ini",
+        _ => "Kata minggu ini",
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    fn goal(kind: &str, target: i64) -> Goal {
+        Goal { id: 1, kind: kind.to_string(), target, manual_only: true, is_active: true, created_at: String::new() }
+    }
+
+    // Satu log manual berisi 10 kata per tanggal
+    fn history(dates: &[&str]) -> BTreeMap<NaiveDate, DayTotals> {
+        dates.iter()
+            .map(|d| (day(d), DayTotals { entries: 1, words: 10, manual_entries: 1, manual_words: 10 }))
+            .collect()
+    }
+
+    fn calendar(holidays: &[&str]) -> Calendar {
+        Calendar { workdays: parse_workdays(None), holidays: holidays.iter().map(|d| (day(d), None)).collect() }
+    }
+
+    fn daily(dates: &[&str], holidays: &[&str], today: &str) -> (i64, i64) {
+        let streak = daily_progress(&goal("daily_entries", 1), &history(dates), &calendar(holidays), day(today)).streak;
+        (streak.current, streak.longest)
+    }
+
+    // Oktober 2026: tanggal 12 & 19 hari Senin, 17-18 akhir pekan
+    #[test]
+    fn weekend_does_not_break_daily_streak() {
+        let dates = ["2026-10-15", "2026-10-16", "2026-10-19", "2026-10-20"];
+        // Hari ini (Rabu) belum tercapai: streak sampai kemarin tetap berlaku
+        assert_eq!(daily(&dates, &[], "2026-10-21"), (4, 4));
+        assert_eq!(daily(&[&dates[..], &["2026-10-21"]].concat(), &[], "2026-10-21"), (5, 5));
+    }
+
+    #[test]
+    fn missed_workday_breaks_daily_streak() {
+        let dates = ["2026-10-08", "2026-10-09", "2026-10-12", "2026-10-13", "2026-10-14", "2026-10-16", "2026-10-19", "2026-10-20"];
+        assert_eq!(daily(&dates, &[], "2026-10-21"), (3, 5));
+        // Kamis 15 ternyata libur: streak menyambung
+        assert_eq!(daily(&dates, &["2026-10-15"], "2026-10-21"), (8, 8));
+        // Kemarin terlewat: streak sekarang putus
+        assert_eq!(daily(&dates[..7], &[], "2026-10-21"), (0, 5));
+    }
+
+    #[test]
+    fn weekend_entries_extend_daily_streak() {
+        assert_eq!(daily(&["2026-10-16", "2026-10-17", "2026-10-19"], &[], "2026-10-19"), (3, 3));
+    }
+
+    #[test]
+    fn manual_only_goal_ignores_imported_entries() {
+        let mut imported = history(&["2026-10-19", "2026-10-20"]);
+        for totals in imported.values_mut() {
+            totals.manual_entries = 0;
+            totals.manual_words = 0;
+        }
+        let mut all_sources = goal("daily_entries", 1);
+        all_sources.manual_only = false;
+
+        assert_eq!(daily_progress(&goal("daily_entries", 1), &imported, &calendar(&[]), day("2026-10-20")).streak.current, 0);
+        assert_eq!(daily_progress(&all_sources, &imported, &calendar(&[]), day("2026-10-20")).streak.current, 2);
+    }
+
+    #[test]
+    fn weekly_streak_counts_consecutive_weeks() {
+        // Minggu 28 Sep, 5 Okt & 12 Okt tercapai (>= 20 kata); minggu berjalan (19 Okt) belum
+        let dates = ["2026-09-21", "2026-09-29", "2026-09-30", "2026-10-05", "2026-10-09", "2026-10-12", "2026-10-18", "2026-10-19"];
+        let progress = weekly_progress(&goal("weekly_words", 20), &history(&dates), day("2026-10-21"));
+        assert_eq!((progress.streak.current, progress.streak.longest), (3, 3));
+        assert_eq!((progress.period_start.as_str(), progress.period_end.as_str()), ("2026-10-19", "2026-10-25"));
+        assert_eq!((progress.current, progress.met), (10, false));
+
+        // Minggu lalu gagal: streak sekarang putus, rekor tetap
+        let progress = weekly_progress(&goal("weekly_words", 20), &history(&dates[..6]), day("2026-10-21"));
+        assert_eq!((progress.streak.current, progress.streak.longest), (0, 2));
+    }
+}
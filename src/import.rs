@@ -0,0 +1,766 @@
+use axum::{
+    extract::{multipart::MultipartRejection, Multipart, Query, State},
+    response::Json,
+    Extension,
+};
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError};
+use crate::logbook::{self, CreateLogRequest};
+
+// Batas upload (zip jurnal bertahun-tahun bisa besar) & batas hasil ekstrak zip
+pub const MAX_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+const MAX_UNZIPPED_BYTES: u64 = 200 * 1024 * 1024;
+
+const FORMATS: &[&str] = &["auto", "noty", "obsidian", "dayone"];
+
+// Jam default untuk catatan harian yang tidak punya jam
+const DEFAULT_TIME: &str = "00:00:00";
+
+// POST /api/import?format=auto&dry_run=true (multipart, satu atau lebih file)
+// File yang diterima: dump JSON Noty, export JSON Day One, catatan Obsidian YYYY-MM-DD.md,
+// atau zip berisi file-file tersebut.
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    pub format: Option<String>, // auto (default): deteksi per file
+    pub dry_run: Option<bool>,  // true = cuma laporan, tidak ada yang disimpan
+}
+
+#[derive(Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub total: usize,
+    pub imported: usize,
+    pub duplicates: usize,
+    pub invalid: usize,
+    pub skipped_files: Vec<SkippedFile>,
+    pub items: Vec<ImportItem>,
+}
+
+#[derive(Serialize)]
+pub struct SkippedFile {
+    pub file: String,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ImportItem {
+    pub origin: String, // Nama file (+ posisi entry untuk file JSON)
+    pub format: &'static str,
+    pub status: &'static str, // new, imported, duplicate, invalid
+    pub entry_date: String,
+    pub entry_time: String,
+    pub source: Option<String>,
+    pub tags: Vec<String>,
+    pub preview: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+}
+
+struct UploadedFile {
+    name: String,
+    data: Vec<u8>,
+}
+
+struct Candidate {
+    origin: String,
+    format: &'static str,
+    entry: CreateLogRequest,
+    dump_id: Option<(String, i64)>, // (file, id entry di server asal), khusus dump Noty
+}
+
+// ==========================================
+// HANDLER
+// ==========================================
+
+pub async fn import_logs(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<ImportQuery>,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> AppResult<Json<ImportReport>> {
+    let multipart = multipart.map_err(|e| AppError::new(e.status(), "invalid_upload", e.body_text()))?;
+    let format = query.format.unwrap_or_else(|| "auto".to_string()).trim().to_lowercase();
+    if !FORMATS.contains(&format.as_str()) {
+        return Err(AppError::validation(vec![FieldError::new(
+            "format",
+            format!("Format '{}' tidak dikenal, pilih dari: {}", format, FORMATS.join(", ")),
+        )]));
+    }
+    let dry_run = query.dry_run.unwrap_or(false);
+
+    let uploads = read_uploads(multipart).await?;
+    if uploads.is_empty() {
+        return Err(AppError::bad_request("Tidak ada file yang di-upload").with_code("invalid_upload"));
+    }
+
+    let mut skipped_files = Vec::new();
+    let mut files = Vec::new();
+    for upload in uploads {
+        if has_extension(&upload.name, "zip") {
+            match unzip(&upload) {
+                Ok(inner) => files.extend(inner),
+                Err(reason) => skipped_files.push(SkippedFile { file: upload.name, reason }),
+            }
+        } else {
+            files.push(upload);
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for file in &files {
+        match parse_file(file, &format) {
+            Ok(found) => candidates.extend(found),
+            Err(reason) => skipped_files.push(SkippedFile { file: file.name.clone(), reason }),
+        }
+    }
+
+    let report = run_import(&pool, user.id, candidates, skipped_files, dry_run).await?;
+    Ok(Json(report))
+}
+
+// Bagian database dari import: cek duplikat, simpan, lalu susun laporan
+async fn run_import(
+    pool: &SqlitePool,
+    user_id: i64,
+    mut candidates: Vec<Candidate>,
+    skipped_files: Vec<SkippedFile>,
+    dry_run: bool,
+) -> AppResult<ImportReport> {
+    let mut report = ImportReport {
+        dry_run,
+        total: candidates.len(),
+        imported: 0,
+        duplicates: 0,
+        invalid: 0,
+        skipped_files,
+        items: Vec::with_capacity(candidates.len()),
+    };
+
+    // [[entry:N]] di dump Noty memakai id server asal. Entry yang berisi link diproses
+    // paling akhir, supaya N bisa diterjemahkan ke id entry di sini sebelum disimpan.
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by_key(|&i| candidates[i].dump_id.is_some() && !crate::links::parse_links(&candidates[i].entry.content).is_empty());
+    let mut items: Vec<Option<ImportItem>> = (0..candidates.len()).map(|_| None).collect();
+    let mut ids: HashMap<(String, i64), i64> = HashMap::new(); // (file dump, id asal) -> id di sini
+
+    // Duplikat = tanggal, jam & isi sama persis, baik dengan data lama maupun sesama file import.
+    // Dry run tetap menyimpan di dalam transaksi (lalu di-rollback) supaya laporannya sama persis.
+    let mut seen = HashMap::new();
+    let mut linked = Vec::new(); // (id, isi) entry baru yang berisi [[entry:..]]
+    let mut tx = pool.begin().await?;
+    for i in order {
+        let candidate = &mut candidates[i];
+        if let Some((file, _)) = &candidate.dump_id {
+            candidate.entry.content = remap_links(&candidate.entry.content, file, &ids);
+        }
+
+        let validation = candidate.entry.validate();
+        let mut item = ImportItem {
+            origin: std::mem::take(&mut candidate.origin),
+            format: candidate.format,
+            status: "new",
+            entry_date: candidate.entry.entry_date.clone(),
+            entry_time: candidate.entry.entry_time.clone(),
+            source: candidate.entry.source.clone(),
+            tags: candidate.entry.tags.clone(),
+            preview: preview(&candidate.entry.content),
+            message: None,
+            id: None,
+        };
+
+        if let Err(e) = validation {
+            item.status = "invalid";
+            item.message = Some(e.message);
+            report.invalid += 1;
+            items[i] = Some(item);
+            continue;
+        }
+
+        let key = (candidate.entry.entry_date.clone(), candidate.entry.entry_time.clone(), candidate.entry.content.clone());
+        // Sesama file dicek duluan, karena entry import sebelumnya sudah tersimpan di transaksi
+        let in_file = seen.get(&key).copied();
+        let existing: Option<i64> = match in_file {
+            Some(_) => None,
+            None => sqlx::query_scalar(
+                "SELECT id FROM log_entries WHERE user_id = ? AND is_deleted = FALSE AND entry_date = ? AND entry_time = ? AND content = ? ORDER BY id LIMIT 1"
+            )
+            .bind(user_id)
+            .bind(&key.0)
+            .bind(&key.1)
+            .bind(&key.2)
+            .fetch_optional(&mut *tx)
+            .await?,
+        };
+
+        let id = if let Some(id) = in_file.or(existing) {
+            item.status = "duplicate";
+            item.message = Some(if existing.is_some() { "Sudah ada di logbook" } else { "Muncul lebih dari sekali di file import" }.to_string());
+            report.duplicates += 1;
+            id
+        } else {
+            let id = logbook::insert_entry(&mut *tx, user_id, &candidate.entry).await?;
+            seen.insert(key, id);
+            if !crate::links::parse_links(&candidate.entry.content).is_empty() {
+                linked.push((id, candidate.entry.content.clone()));
+            }
+            if !dry_run {
+                item.id = Some(id);
+                item.status = "imported";
+                report.imported += 1;
+            }
+            id
+        };
+        // Link ke entry yang duplikat diarahkan ke entry yang sudah ada
+        if let Some(old) = candidate.dump_id.take() {
+            ids.insert(old, id);
+        }
+        items[i] = Some(item);
+    }
+    report.items = items.into_iter().flatten().collect();
+
+    // Import massal gak kirim webhook per entry & gak minta usulan tugas ke AI (lihat
+    // actions::spawn_auto_extract), cukup disimpan sekaligus
+    if dry_run {
+        tx.rollback().await?;
+    } else {
+        tx.commit().await?;
+        crate::categories::sync_from_entries(pool, user_id).await?;
+        // Link baru bisa dicatat setelah commit, karena target-nya bisa sesama entry import
+        for (id, content) in &linked {
+            crate::links::sync_links(pool, user_id, *id, content).await?;
+        }
+        println!("📥 Import user #{}: {} entry masuk, {} duplikat, {} tidak valid.", user_id, report.imported, report.duplicates, report.invalid);
+    }
+
+    Ok(report)
+}
+
+// [[entry:N]] dari dump diarahkan ke id baru; entry asal yang tidak ikut masuk
+// (di trash, tidak valid, atau tidak ada di dump) jadi teks biasa, bukan link ke entry lain
+fn remap_links(content: &str, file: &str, ids: &HashMap<(String, i64), i64>) -> String {
+    crate::links::rewrite_links(content, |old| match ids.get(&(file.to_string(), old)) {
+        Some(id) => format!("[[entry:{}]]", id),
+        None => format!("entry #{}", old),
+    })
+}
+
+async fn read_uploads(mut multipart: Multipart) -> AppResult<Vec<UploadedFile>> {
+    let upload_err = |e: axum::extract::multipart::MultipartError| {
+        AppError::new(e.status(), "invalid_upload", format!("Upload gagal dibaca: {}", e.body_text()))
+    };
+
+    let mut files = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(upload_err)? {
+        // Field tanpa nama file (misal teks biasa) diabaikan
+        let Some(name) = field.file_name().map(str::to_string) else { continue };
+        let data = field.bytes().await.map_err(upload_err)?;
+        files.push(UploadedFile { name, data: data.to_vec() });
+    }
+    Ok(files)
+}
+
+// ==========================================
+// ZIP & DETEKSI FORMAT
+// ==========================================
+
+fn has_extension(name: &str, ext: &str) -> bool {
+    Path::new(name).extension().is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+fn unzip(upload: &UploadedFile) -> Result<Vec<UploadedFile>, String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(&upload.data))
+        .map_err(|e| format!("Zip tidak bisa dibuka: {}", e))?;
+
+    let mut files = Vec::new();
+    let mut budget = MAX_UNZIPPED_BYTES;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| format!("Zip rusak: {}", e))?;
+        if file.is_dir() {
+            continue;
+        }
+        let Some(path) = file.enclosed_name() else { continue };
+        let inner_name = path.to_string_lossy().to_string();
+        // Metadata macOS & file tersembunyi bukan catatan; foto/lampiran Day One juga dilewati
+        let hidden = path.components().any(|c| {
+            let part = c.as_os_str().to_string_lossy();
+            part.starts_with('.') || part == "__MACOSX"
+        });
+        if hidden || !["md", "markdown", "json"].iter().any(|ext| has_extension(&inner_name, ext)) {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        (&mut file).take(budget + 1).read_to_end(&mut data).map_err(|e| format!("Zip rusak: {}", e))?;
+        if data.len() as u64 > budget {
+            return Err(format!("Isi zip lebih dari {} MB", MAX_UNZIPPED_BYTES / 1024 / 1024));
+        }
+        budget -= data.len() as u64;
+
+        files.push(UploadedFile { name: format!("{}/{}", upload.name, inner_name), data });
+    }
+    Ok(files)
+}
+
+fn parse_file(file: &UploadedFile, format: &str) -> Result<Vec<Candidate>, String> {
+    let text = std::str::from_utf8(&file.data).map_err(|_| "File bukan teks UTF-8".to_string())?;
+    let wants = |f: &str| format == "auto" || format == f;
+
+    if has_extension(&file.name, "md") || has_extension(&file.name, "markdown") {
+        if !wants("obsidian") {
+            return Err(format!("Dilewati karena format={}", format));
+        }
+        return parse_obsidian(&file.name, text).map(|c| vec![c]);
+    }
+
+    if has_extension(&file.name, "json") {
+        let json: Value = serde_json::from_str(text).map_err(|e| format!("JSON tidak valid: {}", e))?;
+        if json.get("noty_export").is_some() && wants("noty") {
+            return parse_noty(&file.name, json);
+        }
+        if is_dayone(&json) && wants("dayone") {
+            return parse_dayone(&file.name, json);
+        }
+        return Err("Bukan dump Noty maupun export Day One".to_string());
+    }
+
+    Err("Tipe file tidak didukung (pakai .json, .md, atau .zip)".to_string())
+}
+
+fn preview(content: &str) -> String {
+    let line = content.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    let mut preview: String = line.chars().take(80).collect();
+    if line.chars().count() > 80 {
+        preview.push('…');
+    }
+    preview
+}
+
+// ==========================================
+// FORMAT: DUMP NOTY (hasil /api/export?format=json)
+// ==========================================
+
+#[derive(Deserialize)]
+struct NotyDump {
+    noty_export: u32,
+    #[serde(default)]
+    entries: Vec<NotyEntry>,
+}
+
+#[derive(Deserialize)]
+struct NotyEntry {
+    id: Option<i64>,
+    content: String,
+    entry_date: String,
+    entry_time: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    category: Option<String>,
+    mood: Option<String>,
+    source: Option<String>,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+fn parse_noty(name: &str, json: Value) -> Result<Vec<Candidate>, String> {
+    let dump: NotyDump = serde_json::from_value(json).map_err(|e| format!("Dump Noty tidak valid: {}", e))?;
+    if dump.noty_export > crate::export::DUMP_VERSION {
+        return Err(format!("Dump versi {} lebih baru dari yang didukung server ini ({})", dump.noty_export, crate::export::DUMP_VERSION));
+    }
+
+    Ok(dump.entries.into_iter()
+        .enumerate()
+        // Isi trash tidak ikut dipulihkan
+        .filter(|(_, e)| !e.is_deleted)
+        .map(|(i, e)| Candidate {
+            origin: format!("{}#{}", name, i + 1),
+            format: "noty",
+            entry: CreateLogRequest {
+                content: e.content,
+                entry_date: e.entry_date,
+                entry_time: e.entry_time,
+                tags: e.tags,
+                category: e.category,
+                mood: e.mood,
+                // Source custom dari server lain belum tentu dikenal di sini
+                source: Some(e.source.as_deref().and_then(logbook::known_source).unwrap_or_else(|| "Import".to_string())),
+                template_id: None,
+            },
+            dump_id: e.id.map(|id| (name.to_string(), id)),
+        })
+        .collect())
+}
+
+// ==========================================
+// FORMAT: OBSIDIAN DAILY NOTES (YYYY-MM-DD.md)
+// ==========================================
+
+fn parse_obsidian(name: &str, text: &str) -> Result<Candidate, String> {
+    let stem = Path::new(name).file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let date = NaiveDate::parse_from_str(&stem, "%Y-%m-%d")
+        .map_err(|_| "Nama file catatan harus YYYY-MM-DD.md".to_string())?;
+
+    let (frontmatter, body) = split_frontmatter(text);
+    let mut tags = frontmatter_tags(frontmatter);
+    for tag in inline_tags(body) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    Ok(Candidate {
+        origin: name.to_string(),
+        format: "obsidian",
+        entry: CreateLogRequest {
+            content: body.trim().to_string(),
+            entry_date: date.format("%Y-%m-%d").to_string(),
+            entry_time: DEFAULT_TIME.to_string(),
+            tags,
+            category: None,
+            mood: None,
+            source: Some("Obsidian".to_string()),
+            template_id: None,
+        },
+        dump_id: None,
+    })
+}
+
+// Frontmatter YAML di antara dua baris "---" paling atas
+fn split_frontmatter(text: &str) -> (&str, &str) {
+    let text = text.trim_start_matches('\u{feff}');
+    let Some(rest) = text.strip_prefix("---\n").or_else(|| text.strip_prefix("---\r\n")) else {
+        return ("", text);
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return (&rest[..offset], &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    ("", text)
+}
+
+// Cukup dukung bentuk yang dipakai Obsidian: `tags: [a, b]`, `tags: a, b`, atau daftar `- a`
+fn frontmatter_tags(frontmatter: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    let mut in_list = false;
+
+    for line in frontmatter.lines() {
+        if in_list {
+            if let Some(item) = line.trim_start().strip_prefix("- ") {
+                tags.push(item.to_string());
+                continue;
+            }
+            in_list = false;
+        }
+        let Some(value) = line.strip_prefix("tags:").or_else(|| line.strip_prefix("tag:")) else { continue };
+        let value = value.trim().trim_start_matches('[').trim_end_matches(']');
+        if value.is_empty() {
+            in_list = true;
+        } else {
+            tags.extend(value.split(',').map(str::to_string));
+        }
+    }
+
+    tags.into_iter()
+        .map(|t| t.trim().trim_matches(|c| c == '"' || c == '\'').trim_start_matches('#').to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+// #tag di isi catatan (bukan heading "# Judul" dan bukan di dalam blok kode)
+fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut in_code = false;
+
+    for line in body.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        for word in line.split_whitespace() {
+            let Some(tag) = word.strip_prefix('#') else { continue };
+            let tag: String = tag.chars().take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '/')).collect();
+            // Tag Obsidian minimal punya satu huruf (#2024 bukan tag)
+            if tag.chars().any(char::is_alphabetic) && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
+// ==========================================
+// FORMAT: DAY ONE (Journal.json)
+// ==========================================
+
+#[derive(Deserialize)]
+struct DayOneExport {
+    entries: Vec<DayOneEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayOneEntry {
+    creation_date: String, // RFC 3339, UTC
+    time_zone: Option<String>,
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn is_dayone(json: &Value) -> bool {
+    json.get("metadata").is_some()
+        && json.get("entries")
+            .and_then(Value::as_array)
+            .is_some_and(|entries| entries.iter().all(|e| e.get("creationDate").is_some()))
+}
+
+fn parse_dayone(name: &str, json: Value) -> Result<Vec<Candidate>, String> {
+    let export: DayOneExport = serde_json::from_value(json).map_err(|e| format!("Export Day One tidak valid: {}", e))?;
+
+    Ok(export.entries.into_iter()
+        .enumerate()
+        .map(|(i, e)| {
+            // Tanggal/jam dicatat sesuai zona waktu saat entry dibuat, bukan UTC
+            let (entry_date, entry_time) = match DateTime::parse_from_rfc3339(&e.creation_date) {
+                Ok(at) => {
+                    let local = match e.time_zone.as_deref().and_then(|tz| tz.parse::<chrono_tz::Tz>().ok()) {
+                        Some(tz) => at.with_timezone(&tz).naive_local(),
+                        None => at.with_timezone(&chrono::Local).naive_local(),
+                    };
+                    (local.format("%Y-%m-%d").to_string(), local.format("%H:%M:%S").to_string())
+                }
+                // Biar dilaporkan sebagai invalid oleh validasi
+                Err(_) => (e.creation_date.clone(), String::new()),
+            };
+
+            Candidate {
+                origin: format!("{}#{}", name, i + 1),
+                format: "dayone",
+                entry: CreateLogRequest {
+                    content: unescape_dayone(&e.text),
+                    entry_date,
+                    entry_time,
+                    tags: e.tags,
+                    category: None,
+                    mood: None,
+                    source: Some("Day One".to_string()),
+                    template_id: None,
+                },
+                dump_id: None,
+            }
+        })
+        .collect())
+}
+
+// Day One meng-escape tanda baca Markdown ("1\. Item", "\(catatan\)")
+fn unescape_dayone(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(&next) = chars.peek() {
+                if next.is_ascii_punctuation() {
+                    out.push(next);
+                    chars.next();
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: i64 = 1;
+
+    async fn setup() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        sqlx::query("CREATE TABLE users (id INTEGER PRIMARY KEY, username TEXT NOT NULL)").execute(&pool).await.unwrap();
+        sqlx::query(
+            "CREATE TABLE log_entries (
+                id INTEGER PRIMARY KEY, user_id INTEGER, content TEXT NOT NULL, entry_date DATE NOT NULL, entry_time TIME NOT NULL,
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP, tags TEXT, category TEXT DEFAULT 'General', mood TEXT,
+                source TEXT DEFAULT 'Manual', is_deleted BOOLEAN DEFAULT FALSE
+            )"
+        ).execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO users (id, username) VALUES (?, 'tester')").bind(USER).execute(&pool).await.unwrap();
+        crate::categories::init_schema(&pool).await.unwrap();
+        crate::links::init_schema(&pool).await.unwrap();
+        pool
+    }
+
+    fn candidate(content: &str, date: &str, time: &str) -> Candidate {
+        Candidate {
+            origin: format!("test.json#{}", content),
+            format: "noty",
+            entry: CreateLogRequest {
+                content: content.to_string(),
+                entry_date: date.to_string(),
+                entry_time: time.to_string(),
+                tags: Vec::new(),
+                category: None,
+                mood: None,
+                source: Some("Import".to_string()),
+                template_id: None,
+            },
+            dump_id: None,
+        }
+    }
+
+    async fn contents(pool: &SqlitePool) -> Vec<String> {
+        sqlx::query_scalar("SELECT content FROM log_entries ORDER BY id").fetch_all(pool).await.unwrap()
+    }
+
+    fn statuses(report: &ImportReport) -> Vec<&'static str> {
+        report.items.iter().map(|i| i.status).collect()
+    }
+
+    #[tokio::test]
+    async fn duplicates_against_logbook_and_within_file_are_skipped() {
+        let pool = setup().await;
+        let existing = candidate("sudah ada", "2026-01-01", "08:00:00");
+        logbook::insert_entry(&pool, USER, &existing.entry).await.unwrap();
+
+        let report = run_import(&pool, USER, vec![
+            candidate("sudah ada", "2026-01-01", "08:00:00"),
+            candidate("baru", "2026-01-02", "09:00"),
+            // Jam tanpa detik dinormalisasi dulu, jadi tetap terdeteksi kembar
+            candidate("baru", "2026-01-02", "09:00:00"),
+            candidate("sudah ada", "2026-01-01", "08:00:01"),
+        ], Vec::new(), false).await.unwrap();
+
+        assert_eq!(statuses(&report), ["duplicate", "imported", "duplicate", "imported"]);
+        assert_eq!((report.total, report.imported, report.duplicates, report.invalid), (4, 2, 2, 0));
+        assert_eq!(report.items[0].message.as_deref(), Some("Sudah ada di logbook"));
+        assert_eq!(report.items[2].message.as_deref(), Some("Muncul lebih dari sekali di file import"));
+        assert_eq!(contents(&pool).await, ["sudah ada", "baru", "sudah ada"]);
+    }
+
+    #[tokio::test]
+    async fn entries_in_trash_do_not_count_as_duplicates() {
+        let pool = setup().await;
+        let id = logbook::insert_entry(&pool, USER, &candidate("dihapus", "2026-01-01", "08:00:00").entry).await.unwrap();
+        sqlx::query("UPDATE log_entries SET is_deleted = TRUE WHERE id = ?").bind(id).execute(&pool).await.unwrap();
+
+        let report = run_import(&pool, USER, vec![candidate("dihapus", "2026-01-01", "08:00:00")], Vec::new(), false).await.unwrap();
+        assert_eq!(statuses(&report), ["imported"]);
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_without_saving() {
+        let pool = setup().await;
+        let batch = || vec![
+            candidate("satu", "2026-01-01", "08:00:00"),
+            candidate("satu", "2026-01-01", "08:00:00"),
+            candidate("", "2026-01-01", "08:00:00"),
+            candidate("dua", "2026-02-30", "08:00:00"),
+        ];
+
+        let report = run_import(&pool, USER, batch(), Vec::new(), true).await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!(statuses(&report), ["new", "duplicate", "invalid", "invalid"]);
+        assert_eq!((report.imported, report.duplicates, report.invalid), (0, 1, 2));
+        assert!(report.items.iter().all(|i| i.id.is_none()));
+        assert!(contents(&pool).await.is_empty());
+
+        // Import sungguhan sesudahnya memberi hasil yang sama
+        let report = run_import(&pool, USER, batch(), Vec::new(), false).await.unwrap();
+        assert_eq!(statuses(&report), ["imported", "duplicate", "invalid", "invalid"]);
+        assert_eq!(contents(&pool).await, ["satu"]);
+    }
+
+    #[tokio::test]
+    async fn importing_the_same_batch_twice_adds_nothing() {
+        let pool = setup().await;
+        let batch = || vec![candidate("satu", "2026-01-01", "08:00:00"), candidate("dua", "2026-01-02", "08:00:00")];
+
+        run_import(&pool, USER, batch(), Vec::new(), false).await.unwrap();
+        let report = run_import(&pool, USER, batch(), Vec::new(), false).await.unwrap();
+        assert_eq!((report.imported, report.duplicates), (0, 2));
+        assert_eq!(contents(&pool).await.len(), 2);
+    }
+
+    fn dump(entries: Value) -> Vec<Candidate> {
+        let file = UploadedFile {
+            name: "noty.json".to_string(),
+            data: serde_json::to_vec(&serde_json::json!({ "noty_export": 1, "entries": entries })).unwrap(),
+        };
+        parse_file(&file, "auto").unwrap()
+    }
+
+    async fn links_of(pool: &SqlitePool, source_id: i64) -> Vec<i64> {
+        sqlx::query_scalar("SELECT target_id FROM entry_links WHERE source_id = ? ORDER BY target_id")
+            .bind(source_id)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn dump_links_point_to_the_imported_entries() {
+        let pool = setup().await;
+        // Entry lokal yang kebetulan punya id sama dengan entry di dump
+        for content in ["lokal 1", "lokal 2", "lokal 3"] {
+            logbook::insert_entry(&pool, USER, &candidate(content, "2025-12-01", "08:00:00").entry).await.unwrap();
+        }
+
+        let entry = |id: i64, content: &str, time: &str| serde_json::json!({
+            "id": id, "content": content, "entry_date": "2026-01-01", "entry_time": time
+        });
+        let report = run_import(&pool, USER, dump(serde_json::json!([
+            entry(1, "awal", "08:00:00"),
+            entry(2, "lanjutan [[entry:1]] sebelum [[entry:3]]", "09:00:00"),
+            entry(3, "akhir", "10:00:00"),
+            entry(4, "rujuk yang hilang [[entry:9]]", "11:00:00"),
+            { "id": 9, "content": "di trash", "entry_date": "2026-01-01", "entry_time": "12:00:00", "is_deleted": true },
+        ])), Vec::new(), false).await.unwrap();
+
+        // Urutan laporan tetap mengikuti file
+        let origins: Vec<&str> = report.items.iter().map(|i| i.origin.as_str()).collect();
+        assert_eq!(origins, ["noty.json#1", "noty.json#2", "noty.json#3", "noty.json#4"]);
+        let ids: Vec<i64> = report.items.iter().map(|i| i.id.unwrap()).collect();
+
+        let content: String = sqlx::query_scalar("SELECT content FROM log_entries WHERE id = ?").bind(ids[1]).fetch_one(&pool).await.unwrap();
+        assert_eq!(content, format!("lanjutan [[entry:{}]] sebelum [[entry:{}]]", ids[0], ids[2]));
+        assert_eq!(links_of(&pool, ids[1]).await, [ids[0], ids[2]]);
+
+        let content: String = sqlx::query_scalar("SELECT content FROM log_entries WHERE id = ?").bind(ids[3]).fetch_one(&pool).await.unwrap();
+        assert_eq!(content, "rujuk yang hilang entry #9");
+        assert!(links_of(&pool, ids[3]).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reimporting_a_dump_with_links_finds_duplicates() {
+        let pool = setup().await;
+        let entries = || serde_json::json!([
+            { "id": 1, "content": "awal", "entry_date": "2026-01-01", "entry_time": "08:00:00" },
+            { "id": 2, "content": "lihat [[entry:1]]", "entry_date": "2026-01-01", "entry_time": "09:00:00" },
+        ]);
+
+        let first = run_import(&pool, USER, dump(entries()), Vec::new(), false).await.unwrap();
+        assert_eq!(first.imported, 2);
+        let second = run_import(&pool, USER, dump(entries()), Vec::new(), false).await.unwrap();
+        assert_eq!((second.imported, second.duplicates), (0, 2));
+    }
+}
@@ -0,0 +1,415 @@
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use std::time::Duration;
+
+use crate::github_sync::GitHubIntegration;
+use crate::logbook::{self, CreateLogRequest};
+use crate::notion_sync::NotionIntegration;
+use crate::telegram::TelegramIntegration;
+
+// ==========================================
+// KONTRAK INTEGRASI
+// ==========================================
+
+// Satu item mentah dari service eksternal (commit, pesan chat, perintah bot, dll)
+pub struct ExternalItem {
+    pub external_id: String, // ID unik di service asal, dipakai buat cek duplikat
+    pub payload: Value,
+    // Cursor/offset yang disimpan begitu item ini selesai diproses (None = tidak ada cursor)
+    pub state: Option<Value>,
+}
+
+// Hasil satu kali poll
+#[derive(Default)]
+pub struct PollResult {
+    pub items: Vec<ExternalItem>,
+    // Cursor setelah item terakhir (misal melewati update yang diabaikan di ujung batch);
+    // hanya disimpan kalau semua item berhasil diproses
+    pub next_state: Option<Value>,
+}
+
+// Baris `integrations` yang sudah di-parse
+#[derive(Clone)]
+pub struct IntegrationConfig {
+    pub id: i64,
+    pub user_id: i64, // Pemilik integrasi, entri hasil impor masuk ke logbook user ini
+    pub api_key: String,
+    pub config: Value, // Isi config_json (selalu object)
+    pub is_active: bool,
+    pub mode: String,
+    pub state: Value, // Isi state_json: cursor/offset milik integrasi, bukan input user
+}
+
+#[async_trait]
+pub trait Integration: Send + Sync {
+    // Nilai kolom `integrations.service_name` & path di /api/integrations/{service}
+    fn service_name(&self) -> &'static str;
+
+    // Jeda antar polling
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(60)
+    }
+
+    // Rapikan config dari user sebelum disimpan (trim, isi default, dll)
+    fn configure(&self, config: Value) -> Result<Value, String> {
+        Ok(config)
+    }
+
+    // Cek config sudah lengkap sebelum integrasi boleh jalan
+    fn validate(&self, config: &IntegrationConfig) -> Result<(), String>;
+
+    // Ambil item baru dari service eksternal
+    async fn poll(&self, pool: &SqlitePool, config: &IntegrationConfig) -> Result<PollResult, String>;
+
+    // Ubah item mentah jadi entri logbook (None = item diabaikan)
+    fn to_log_entry(&self, item: &ExternalItem) -> Option<CreateLogRequest>;
+
+    // Item yang bukan entri log (misal perintah bot). Dijalankan berurutan bersama item lain,
+    // jadi perintah melihat entri yang masuk sebelumnya di batch yang sama. true = sudah ditangani.
+    async fn handle_command(&self, _pool: &SqlitePool, _config: &IntegrationConfig, _item: &ExternalItem) -> bool {
+        false
+    }
+
+    // Dipanggil setelah item berhasil masuk logbook (misal kirim konfirmasi balik)
+    async fn after_import(&self, _config: &IntegrationConfig, _item: &ExternalItem, _log_id: i64) {}
+}
+
+// ==========================================
+// REGISTRY
+// ==========================================
+
+// Integrasi baru cukup didaftarkan di sini, gak perlu sentuh main.rs
+static REGISTRY: &[&dyn Integration] = &[
+    &GitHubIntegration,
+    &TelegramIntegration,
+    &NotionIntegration,
+];
+
+pub fn registry() -> &'static [&'static dyn Integration] {
+    REGISTRY
+}
+
+pub fn find(service: &str) -> Option<&'static dyn Integration> {
+    REGISTRY.iter().copied().find(|i| i.service_name() == service)
+}
+
+// ==========================================
+// SKEMA & STATUS
+// ==========================================
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    // Kolom status per integrasi
+    crate::db::add_column_if_missing(pool, "integrations", "last_success_at", "DATETIME").await?;
+    crate::db::add_column_if_missing(pool, "integrations", "last_error", "TEXT").await?;
+    crate::db::add_column_if_missing(pool, "integrations", "last_error_at", "DATETIME").await?;
+    crate::db::add_column_if_missing(pool, "integrations", "items_imported", "INTEGER DEFAULT 0").await?;
+    crate::db::add_column_if_missing(pool, "integrations", "state_json", "TEXT").await?;
+
+    // Referensi item eksternal -> log entry (pengganti cek duplikat via LIKE).
+    // Unik per user: dua user boleh mengimpor commit/pesan dengan ID yang sama.
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS external_refs (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER REFERENCES users(id),
+            service_name TEXT NOT NULL,
+            external_id TEXT NOT NULL,
+            log_entry_id INTEGER REFERENCES log_entries(id),
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(user_id, service_name, external_id)
+        );"
+    ).execute(pool).await?;
+    // Dipakai integrasi keluar (Notion) buat deteksi entri yang diedit sejak sync terakhir
+    crate::db::add_column_if_missing(pool, "external_refs", "sync_fingerprint", "TEXT").await?;
+
+    // Data lama: external_refs versi single-user, constraint UNIQUE-nya harus diganti
+    // jadi tabelnya dibangun ulang (SQLite gak bisa ubah constraint)
+    if !crate::db::has_column(pool, "external_refs", "user_id").await? {
+        let mut tx = pool.begin().await?;
+        sqlx::query(
+            "CREATE TABLE external_refs_new (
+                id INTEGER PRIMARY KEY,
+                user_id INTEGER REFERENCES users(id),
+                service_name TEXT NOT NULL,
+                external_id TEXT NOT NULL,
+                log_entry_id INTEGER REFERENCES log_entries(id),
+                created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+                sync_fingerprint TEXT,
+                UNIQUE(user_id, service_name, external_id)
+            );"
+        ).execute(&mut *tx).await?;
+        sqlx::query(
+            "INSERT INTO external_refs_new (id, user_id, service_name, external_id, log_entry_id, created_at, sync_fingerprint)
+             SELECT r.id, l.user_id, r.service_name, r.external_id, r.log_entry_id, r.created_at, r.sync_fingerprint
+             FROM external_refs r LEFT JOIN log_entries l ON l.id = r.log_entry_id"
+        ).execute(&mut *tx).await?;
+        sqlx::query("DROP TABLE external_refs").execute(&mut *tx).await?;
+        sqlx::query("ALTER TABLE external_refs_new RENAME TO external_refs").execute(&mut *tx).await?;
+        tx.commit().await?;
+    }
+
+    // Data lama: config GitHub dulu disimpan sebagai string repo polos
+    sqlx::query(
+        "UPDATE integrations SET config_json = json_object('repo_name', config_json)
+         WHERE service_name = 'github' AND config_json IS NOT NULL AND json_valid(config_json) = 0"
+    ).execute(pool).await?;
+
+    // Data lama: commit GitHub yang sudah masuk sebelum ada external_refs
+    sqlx::query(
+        "INSERT OR IGNORE INTO external_refs (user_id, service_name, external_id, log_entry_id)
+         SELECT user_id, 'github', substr(content, instr(content, 'Commit ID: ') + 11, 40), id
+         FROM log_entries WHERE source = 'GitHub' AND instr(content, 'Commit ID: ') > 0"
+    ).execute(pool).await?;
+
+    Ok(())
+}
+
+#[derive(Serialize, sqlx::FromRow, Default)]
+pub struct IntegrationStatus {
+    pub last_synced_at: Option<String>,
+    pub last_success_at: Option<String>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<String>,
+    pub items_imported: i64,
+}
+
+pub async fn load_status(pool: &SqlitePool, user_id: i64, service: &str) -> Result<Option<IntegrationStatus>, sqlx::Error> {
+    sqlx::query_as::<_, IntegrationStatus>(
+        "SELECT CAST(last_synced_at AS TEXT) as last_synced_at, CAST(last_success_at AS TEXT) as last_success_at,
+                last_error, CAST(last_error_at AS TEXT) as last_error_at, COALESCE(items_imported, 0) as items_imported
+         FROM integrations WHERE user_id = ? AND service_name = ? LIMIT 1"
+    )
+    .bind(user_id)
+    .bind(service)
+    .fetch_optional(pool)
+    .await
+}
+
+#[derive(sqlx::FromRow)]
+struct IntegrationRow {
+    id: i64,
+    user_id: i64,
+    api_key: Option<String>,
+    config_json: Option<String>,
+    is_active: Option<bool>,
+    mode: Option<String>,
+    state_json: Option<String>,
+}
+
+impl From<IntegrationRow> for IntegrationConfig {
+    fn from(row: IntegrationRow) -> Self {
+        let parse_object = |raw: Option<String>| raw
+            .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+            .filter(|c| c.is_object())
+            .unwrap_or(Value::Object(Default::default()));
+
+        IntegrationConfig {
+            id: row.id,
+            user_id: row.user_id,
+            api_key: crate::crypto::reveal(row.api_key).unwrap_or_default(), // Secret di DB terenkripsi
+            config: parse_object(row.config_json),
+            is_active: row.is_active.unwrap_or(false),
+            mode: row.mode.unwrap_or("notify_only".to_string()),
+            state: parse_object(row.state_json),
+        }
+    }
+}
+
+const CONFIG_COLUMNS: &str = "id, user_id, api_key, config_json, is_active, mode, state_json";
+
+pub async fn load_config(pool: &SqlitePool, user_id: i64, service: &str) -> Result<Option<IntegrationConfig>, sqlx::Error> {
+    let row = sqlx::query_as::<_, IntegrationRow>(
+        &format!("SELECT {} FROM integrations WHERE user_id = ? AND service_name = ? LIMIT 1", CONFIG_COLUMNS)
+    )
+    .bind(user_id)
+    .bind(service)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row.map(IntegrationConfig::from))
+}
+
+// Integrasi aktif milik semua user
+async fn load_active(pool: &SqlitePool, service: &str) -> Result<Vec<IntegrationConfig>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, IntegrationRow>(
+        &format!("SELECT {} FROM integrations WHERE service_name = ? AND is_active = TRUE AND user_id IS NOT NULL", CONFIG_COLUMNS)
+    )
+    .bind(service)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().map(IntegrationConfig::from).collect())
+}
+
+// Simpan cursor/offset integrasi supaya polling berikutnya lanjut dari situ
+pub async fn save_state(pool: &SqlitePool, id: i64, state: &Value) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE integrations SET state_json = ? WHERE id = ?")
+        .bind(state.to_string())
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+async fn record_success(pool: &SqlitePool, id: i64, imported: i64) {
+    let _ = sqlx::query(
+        "UPDATE integrations SET last_synced_at = CURRENT_TIMESTAMP, last_success_at = CURRENT_TIMESTAMP,
+                last_error = NULL, items_imported = COALESCE(items_imported, 0) + ? WHERE id = ?"
+    )
+    .bind(imported)
+    .bind(id)
+    .execute(pool)
+    .await;
+}
+
+async fn record_error(pool: &SqlitePool, id: i64, error: &str) {
+    let _ = sqlx::query(
+        "UPDATE integrations SET last_synced_at = CURRENT_TIMESTAMP, last_error = ?, last_error_at = CURRENT_TIMESTAMP WHERE id = ?"
+    )
+    .bind(error)
+    .bind(id)
+    .execute(pool)
+    .await;
+}
+
+// ==========================================
+// BACKGROUND POLLING
+// ==========================================
+
+pub fn spawn_pollers(pool: SqlitePool) {
+    for integration in registry() {
+        let pool = pool.clone();
+        tokio::spawn(async move {
+            run_poller(pool, *integration).await;
+        });
+    }
+}
+
+async fn run_poller(pool: SqlitePool, integration: &'static dyn Integration) {
+    let service = integration.service_name();
+    println!("👀 [{}] Watcher Service Started...", service);
+
+    loop {
+        let configs = load_active(&pool, service).await.unwrap_or_else(|e| {
+            println!("❌ [{}] Gagal baca konfigurasi: {}", service, e);
+            Vec::new()
+        });
+
+        for config in configs {
+            if let Err(e) = integration.validate(&config) {
+                record_error(&pool, config.id, &e).await;
+                continue;
+            }
+
+            match integration.poll(&pool, &config).await {
+                Ok(result) => {
+                    let (imported, outcome) = import_items(&pool, integration, &config, result).await;
+                    if imported > 0 {
+                        println!("✨ [{}] {} item baru masuk logbook.", service, imported);
+                        crate::webhooks::notify(
+                            &pool,
+                            config.user_id,
+                            &format!("{}_import", service),
+                            serde_json::json!({ "service": service, "count": imported }),
+                        ).await;
+                    }
+
+                    match outcome {
+                        Ok(()) => record_success(&pool, config.id, imported).await,
+                        Err(e) => {
+                            println!("❌ [{}] {}", service, e);
+                            record_error(&pool, config.id, &e).await;
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("⚠️ [{}] Sync gagal: {}", service, e);
+                    record_error(&pool, config.id, &e).await;
+                }
+            }
+        }
+
+        tokio::time::sleep(integration.poll_interval()).await;
+    }
+}
+
+// Proses item berurutan & simpan item yang belum pernah masuk; balikin jumlah yang masuk.
+// Cursor item disimpan begitu item itu selesai. Error database menghentikan batch tanpa
+// menggeser cursor, jadi item itu & sesudahnya diambil ulang di poll berikutnya.
+// Item yang tidak lolos validasi dilewati (dan cursor-nya tetap maju).
+async fn import_items(
+    pool: &SqlitePool,
+    integration: &dyn Integration,
+    config: &IntegrationConfig,
+    result: PollResult,
+) -> (i64, Result<(), String>) {
+    let mut imported = 0;
+    for item in &result.items {
+        match import_item(pool, integration, config, item).await {
+            Ok(true) => imported += 1,
+            Ok(false) => {}
+            Err(e) => return (imported, Err(format!("Item {} gagal disimpan, dicoba lagi nanti: {}", item.external_id, e))),
+        }
+        if let Some(state) = &item.state {
+            if let Err(e) = save_state(pool, config.id, state).await {
+                return (imported, Err(format!("Gagal simpan state: {}", e)));
+            }
+        }
+    }
+
+    if let Some(state) = &result.next_state {
+        if let Err(e) = save_state(pool, config.id, state).await {
+            return (imported, Err(format!("Gagal simpan state: {}", e)));
+        }
+    }
+    (imported, Ok(()))
+}
+
+// true = item masuk logbook sebagai entri baru
+async fn import_item(
+    pool: &SqlitePool,
+    integration: &dyn Integration,
+    config: &IntegrationConfig,
+    item: &ExternalItem,
+) -> Result<bool, sqlx::Error> {
+    let service = integration.service_name();
+
+    if integration.handle_command(pool, config, item).await {
+        return Ok(false);
+    }
+
+    let exists: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM external_refs WHERE user_id = ? AND service_name = ? AND external_id = ?)"
+    )
+    .bind(config.user_id)
+    .bind(service)
+    .bind(&item.external_id)
+    .fetch_one(pool)
+    .await?;
+    if exists {
+        return Ok(false);
+    }
+
+    let Some(mut entry) = integration.to_log_entry(item) else { return Ok(false) };
+
+    // Aturan yang sama dengan input manual (panjang isi, tanggal/jam, source, mood)
+    if let Err(e) = entry.validate() {
+        println!("⚠️ [{}] Item {} dilewati: {}", service, item.external_id, e.message);
+        return Ok(false);
+    }
+
+    let log_id = logbook::insert_log(pool, config.user_id, entry).await?;
+    sqlx::query(
+        "INSERT OR IGNORE INTO external_refs (user_id, service_name, external_id, log_entry_id) VALUES (?, ?, ?, ?)"
+    )
+    .bind(config.user_id)
+    .bind(service)
+    .bind(&item.external_id)
+    .bind(log_id)
+    .execute(pool)
+    .await?;
+
+    integration.after_import(config, item, log_id).await;
+    Ok(true)
+}
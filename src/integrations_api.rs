@@ -1,82 +1,180 @@
 use axum::{
-    extract::State,
+    extract::{Path, State},
     response::Json,
-    Json as JsonBody,
+    Extension,
 };
 use sqlx::SqlitePool;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, JsonBody};
+use crate::integrations::{self, IntegrationConfig, IntegrationStatus};
+
+const MODES: &[&str] = &["notify_only", "ai_analysis", "full_sync"];
 
 #[derive(Serialize)]
-pub struct GithubConfigResponse {
-    pub repo_name: String,
+pub struct IntegrationResponse {
+    pub service: String,
     pub is_active: bool,
-    pub is_token_set: bool, // Kita sembunyikan token aslinya
+    pub mode: String,
+    pub is_api_key_set: bool, // Kita sembunyikan key/token aslinya
+    pub config: Value,
+    pub status: IntegrationStatus,
 }
 
 #[derive(Deserialize)]
-pub struct GithubConfigReq {
-    pub repo_name: String,
-    pub token: String, // Bisa kosong kalau gak mau update
-    pub is_active: bool,
+pub struct UpdateIntegrationRequest {
+    pub api_key: Option<String>, // Kosong / null = jangan timpa key lama
+    pub config: Option<Value>,
+    pub is_active: Option<bool>,
+    pub mode: Option<String>,
+}
+
+async fn build_response(pool: &SqlitePool, user_id: i64, service: &str) -> AppResult<IntegrationResponse> {
+    let config = integrations::load_config(pool, user_id, service).await?;
+    let status = integrations::load_status(pool, user_id, service).await?;
+
+    Ok(match config {
+        Some(c) => IntegrationResponse {
+            service: service.to_string(),
+            is_active: c.is_active,
+            mode: c.mode,
+            is_api_key_set: !c.api_key.is_empty(),
+            config: c.config,
+            status: status.unwrap_or_default(),
+        },
+        None => IntegrationResponse {
+            service: service.to_string(),
+            is_active: false,
+            mode: "notify_only".to_string(),
+            is_api_key_set: false,
+            config: Value::Object(Default::default()),
+            status: IntegrationStatus::default(),
+        },
+    })
+}
+
+fn unknown_service(service: &str) -> AppError {
+    AppError::not_found(format!("Integrasi '{}' tidak dikenal", service))
+}
+
+// GET semua integrasi yang terdaftar
+pub async fn list_integrations(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<IntegrationResponse>>> {
+    let mut list = Vec::new();
+    for integration in integrations::registry() {
+        list.push(build_response(&pool, user.id, integration.service_name()).await?);
+    }
+    Ok(Json(list))
 }
 
 // GET Config
-pub async fn get_github_config(
+pub async fn get_integration(
     State(pool): State<SqlitePool>,
-) -> Json<GithubConfigResponse> {
-    let row = sqlx::query!(
-        "SELECT config_json, is_active, api_key FROM integrations WHERE service_name = 'github' LIMIT 1"
-    )
-    .fetch_optional(&pool)
-    .await
-    .unwrap_or(None);
-
-    if let Some(r) = row {
-        Json(GithubConfigResponse {
-            repo_name: r.config_json.unwrap_or_default(),
-            is_active: r.is_active.unwrap_or(false),
-            is_token_set: r.api_key.is_some() && !r.api_key.unwrap().is_empty(),
-        })
-    } else {
-        Json(GithubConfigResponse {
-            repo_name: "".to_string(),
-            is_active: false,
-            is_token_set: false,
-        })
+    Extension(user): Extension<AuthUser>,
+    Path(service): Path<String>,
+) -> AppResult<Json<IntegrationResponse>> {
+    if integrations::find(&service).is_none() {
+        return Err(unknown_service(&service));
     }
+
+    Ok(Json(build_response(&pool, user.id, &service).await?))
 }
 
 // UPDATE Config
-pub async fn update_github_config(
+pub async fn update_integration(
     State(pool): State<SqlitePool>,
-    JsonBody(payload): JsonBody<GithubConfigReq>,
-) -> Json<String> {
-    
-    // Cek apakah data sudah ada
-    let exists = sqlx::query!("SELECT count(*) as count FROM integrations WHERE service_name = 'github'")
-        .fetch_one(&pool).await.unwrap();
-
-    if exists.count == 0 {
-        // Insert Baru
-        sqlx::query!(
-            "INSERT INTO integrations (service_name, config_json, api_key, is_active) VALUES ('github', ?, ?, ?)",
-            payload.repo_name, payload.token, payload.is_active
-        ).execute(&pool).await.unwrap();
-    } else {
-        // Update yang ada
-        // Kalau token kosong, jangan ditimpa (biarkan yang lama)
-        if payload.token.trim().is_empty() {
-            sqlx::query!(
-                "UPDATE integrations SET config_json = ?, is_active = ? WHERE service_name = 'github'",
-                payload.repo_name, payload.is_active
-            ).execute(&pool).await.unwrap();
-        } else {
-            sqlx::query!(
-                "UPDATE integrations SET config_json = ?, api_key = ?, is_active = ? WHERE service_name = 'github'",
-                payload.repo_name, payload.token, payload.is_active
-            ).execute(&pool).await.unwrap();
+    Extension(user): Extension<AuthUser>,
+    Path(service): Path<String>,
+    JsonBody(payload): JsonBody<UpdateIntegrationRequest>,
+) -> AppResult<Json<IntegrationResponse>> {
+    let Some(integration) = integrations::find(&service) else {
+        return Err(unknown_service(&service));
+    };
+    let invalid_config = |msg: String| AppError::bad_request(msg).with_code("invalid_config");
+
+    if let Some(mode) = &payload.mode {
+        if !MODES.contains(&mode.as_str()) {
+            return Err(AppError::bad_request(format!("mode harus salah satu dari: {}", MODES.join(", "))));
+        }
+    }
+
+    // Gabungkan dengan config lama, field yang gak dikirim tetap pakai nilai lama
+    let current = integrations::load_config(&pool, user.id, &service).await?;
+
+    // Key lama disimpan apa adanya (ciphertext tidak ditulis ulang). Kalau gagal didekripsi
+    // (misal master key salah), tolak; jangan sampai dianggap kosong lalu tertimpa NULL.
+    let (api_key, stored_key) = match payload.api_key.map(|k| k.trim().to_string()).filter(|k| !k.is_empty()) {
+        Some(new_key) => {
+            let encrypted = crate::crypto::encrypt(&new_key);
+            (new_key, Some(encrypted))
+        }
+        None => {
+            let stored: Option<String> = match &current {
+                Some(c) => sqlx::query_scalar("SELECT api_key FROM integrations WHERE id = ?")
+                    .bind(c.id)
+                    .fetch_one(&pool)
+                    .await?,
+                None => None,
+            };
+            match stored.filter(|k| !k.is_empty()) {
+                Some(stored) => {
+                    let plain = crate::crypto::decrypt(&stored).map_err(|e| AppError::conflict(format!(
+                        "API key tersimpan tidak bisa dibaca ({}); kirim ulang api_key", e
+                    )).with_code("secret_unreadable"))?;
+                    (plain, Some(stored))
+                }
+                None => (String::new(), None),
+            }
         }
+    };
+    let raw_config = payload.config
+        .or(current.as_ref().map(|c| c.config.clone()))
+        .unwrap_or(Value::Object(Default::default()));
+    let config = integration.configure(raw_config).map_err(invalid_config)?;
+    let mode = payload.mode
+        .or(current.as_ref().map(|c| c.mode.clone()))
+        .unwrap_or("notify_only".to_string());
+    let is_active = payload.is_active
+        .unwrap_or(current.as_ref().map(|c| c.is_active).unwrap_or(false));
+
+    // Integrasi yang mau diaktifkan harus lolos validasi dulu
+    if is_active {
+        let candidate = IntegrationConfig {
+            id: current.as_ref().map(|c| c.id).unwrap_or_default(),
+            user_id: user.id,
+            api_key: api_key.clone(),
+            config: config.clone(),
+            is_active,
+            mode: mode.clone(),
+            state: current.as_ref().map(|c| c.state.clone()).unwrap_or(Value::Null),
+        };
+        integration.validate(&candidate).map_err(invalid_config)?;
     }
 
-    Json("GitHub Config Updated".to_string())
-}
\ No newline at end of file
+    let config_json = config.to_string();
+
+    match current {
+        None => {
+            // Insert Baru
+            sqlx::query(
+                "INSERT INTO integrations (user_id, service_name, config_json, api_key, is_active, mode) VALUES (?, ?, ?, ?, ?, ?)"
+            )
+            .bind(user.id).bind(&service).bind(config_json).bind(stored_key).bind(is_active).bind(mode)
+            .execute(&pool).await?;
+        }
+        Some(current) => {
+            // Update yang ada
+            sqlx::query(
+                "UPDATE integrations SET config_json = ?, api_key = ?, is_active = ?, mode = ? WHERE id = ?"
+            )
+            .bind(config_json).bind(stored_key).bind(is_active).bind(mode).bind(current.id)
+            .execute(&pool).await?;
+        }
+    }
+
+    Ok(Json(build_response(&pool, user.id, &service).await?))
+}
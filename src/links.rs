@@ -0,0 +1,276 @@
+use axum::{
+    extract::{Query, State},
+    response::Json,
+    Extension,
+};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::BTreeSet;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError};
+
+// Sintaks link di isi log: [[entry:123]]
+const LINK_OPEN: &str = "[[entry:";
+const LINK_CLOSE: &str = "]]";
+
+const EXCERPT_MAX: usize = 80;
+const DEFAULT_DAYS: i64 = 30;
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS entry_links (
+            source_id INTEGER NOT NULL REFERENCES log_entries(id) ON DELETE CASCADE, -- Log yang menulis link
+            target_id INTEGER NOT NULL REFERENCES log_entries(id) ON DELETE CASCADE, -- Log yang dirujuk
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (source_id, target_id)
+        );"
+    ).execute(pool).await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_entry_links_target ON entry_links(target_id)")
+        .execute(pool)
+        .await?;
+
+    // Log lama yang sudah berisi [[entry:..]] sebelum fitur ini ada
+    let pending: Vec<(i64, i64, String)> = sqlx::query_as(
+        "SELECT id, user_id, content FROM log_entries
+         WHERE content LIKE '%[[entry:%' AND id NOT IN (SELECT source_id FROM entry_links)"
+    )
+    .fetch_all(pool)
+    .await?;
+    for (id, user_id, content) in pending {
+        sync_links(pool, user_id, id, &content).await?;
+    }
+
+    Ok(())
+}
+
+// Semua id unik yang dirujuk lewat [[entry:123]] (spasi di sekitar angka diabaikan)
+pub fn parse_links(content: &str) -> BTreeSet<i64> {
+    let mut ids = BTreeSet::new();
+    let mut rest = content;
+    while let Some(start) = rest.find(LINK_OPEN) {
+        rest = &rest[start + LINK_OPEN.len()..];
+        if let Some(end) = rest.find(LINK_CLOSE) {
+            if let Ok(id) = rest[..end].trim().parse::<i64>() {
+                ids.insert(id);
+            }
+        }
+    }
+    ids
+}
+
+// Tulis ulang link keluar milik satu log. Link ke log orang lain, diri sendiri,
+// atau log yang tidak ada diabaikan diam-diam.
+pub async fn sync_links(pool: &SqlitePool, user_id: i64, source_id: i64, content: &str) -> Result<usize, sqlx::Error> {
+    let targets = parse_links(content);
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM entry_links WHERE source_id = ?")
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+
+    let mut linked = 0;
+    for target_id in targets.into_iter().filter(|t| *t != source_id) {
+        linked += sqlx::query(
+            "INSERT OR IGNORE INTO entry_links (source_id, target_id, user_id)
+             SELECT ?, id, user_id FROM log_entries WHERE id = ? AND user_id = ?"
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as usize;
+    }
+    tx.commit().await?;
+
+    Ok(linked)
+}
+
+// ==========================================
+// LINK & BACKLINK SATU LOG
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct LinkedEntry {
+    pub id: i64,
+    pub entry_date: String,
+    pub entry_time: String,
+    pub category: String,
+    #[serde(skip)]
+    pub content: String,
+    #[sqlx(skip)]
+    pub excerpt: String,
+}
+
+// Ganti setiap [[entry:123]] dengan hasil `to(123)` (cuplikan, import dump, render HTML)
+pub fn rewrite_links(text: &str, to: impl Fn(i64) -> String) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(LINK_OPEN) {
+        let after = &rest[start + LINK_OPEN.len()..];
+        let Some(end) = after.find(LINK_CLOSE) else { break };
+        out.push_str(&rest[..start]);
+        match after[..end].trim().parse::<i64>() {
+            Ok(id) => out.push_str(&to(id)),
+            Err(_) => out.push_str(&rest[start..start + LINK_OPEN.len() + end + LINK_CLOSE.len()]),
+        }
+        rest = &after[end + LINK_CLOSE.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// Baris pertama isi log tanpa tanda Markdown di depannya, dipotong
+pub fn excerpt(content: &str) -> String {
+    let line = content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("");
+    let line = rewrite_links(line.trim_start_matches(['#', '>', '-', '*', ' ']), |id| format!("#{}", id));
+    if line.chars().count() > EXCERPT_MAX {
+        format!("{}…", line.chars().take(EXCERPT_MAX).collect::<String>().trim_end())
+    } else {
+        line.to_string()
+    }
+}
+
+async fn linked(pool: &SqlitePool, user_id: i64, sql: &str, id: i64) -> Result<Vec<LinkedEntry>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, LinkedEntry>(sql)
+        .bind(id)
+        .bind(user_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(|mut e| { e.excerpt = excerpt(&e.content); e }).collect())
+}
+
+// Log yang dirujuk oleh log ini
+pub async fn outgoing(pool: &SqlitePool, user_id: i64, id: i64) -> Result<Vec<LinkedEntry>, sqlx::Error> {
+    linked(pool, user_id,
+        "SELECT l.id, l.entry_date, l.entry_time, COALESCE(l.category, 'General') as category, l.content
+         FROM entry_links k JOIN log_entries l ON l.id = k.target_id
+         WHERE k.source_id = ? AND l.user_id = ? AND l.is_deleted = FALSE
+         ORDER BY l.entry_date ASC, l.entry_time ASC",
+        id,
+    ).await
+}
+
+// Log lain yang merujuk ke log ini (backlink)
+pub async fn backlinks(pool: &SqlitePool, user_id: i64, id: i64) -> Result<Vec<LinkedEntry>, sqlx::Error> {
+    linked(pool, user_id,
+        "SELECT l.id, l.entry_date, l.entry_time, COALESCE(l.category, 'General') as category, l.content
+         FROM entry_links k JOIN log_entries l ON l.id = k.source_id
+         WHERE k.target_id = ? AND l.user_id = ? AND l.is_deleted = FALSE
+         ORDER BY l.entry_date DESC, l.entry_time DESC",
+        id,
+    ).await
+}
+
+// ==========================================
+// GRAPH
+// ==========================================
+
+// GET /api/logs/graph?from=2024-01-01&to=2024-01-31&include_isolated=false
+#[derive(Deserialize)]
+pub struct GraphQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub include_isolated: Option<bool>, // Ikutkan log dalam rentang yang tidak punya link
+}
+
+#[derive(Serialize)]
+pub struct Graph {
+    pub from: String,
+    pub to: String,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct GraphNode {
+    pub id: i64,
+    pub entry_date: String,
+    pub entry_time: String,
+    pub category: String,
+    pub mood: Option<String>,
+    pub in_range: bool, // false = di luar rentang, ikut karena terhubung ke log di dalam rentang
+    #[serde(skip)]
+    pub content: String,
+    #[sqlx(skip)]
+    pub label: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct GraphEdge {
+    pub source: i64,
+    pub target: i64,
+}
+
+pub async fn graph(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<GraphQuery>,
+) -> AppResult<Json<Graph>> {
+    let mut errors = Vec::new();
+    let mut parse_date = |field: &'static str, raw: Option<String>| {
+        let raw = raw.filter(|r| !r.trim().is_empty())?;
+        match NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                errors.push(FieldError::new(field, format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", raw)));
+                None
+            }
+        }
+    };
+    let from = parse_date("from", query.from);
+    let to = parse_date("to", query.to).unwrap_or_else(|| chrono::Local::now().date_naive());
+    let from = from.unwrap_or(to - Duration::days(DEFAULT_DAYS - 1));
+    if from > to {
+        errors.push(FieldError::new("to", "Tanggal akhir tidak boleh sebelum tanggal awal"));
+    }
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+    let (from, to) = (from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string());
+
+    // Edge yang salah satu ujungnya ada di rentang; log di trash tidak ikut
+    let edges: Vec<GraphEdge> = sqlx::query_as(
+        "SELECT k.source_id as source, k.target_id as target
+         FROM entry_links k
+         JOIN log_entries s ON s.id = k.source_id AND s.is_deleted = FALSE
+         JOIN log_entries t ON t.id = k.target_id AND t.is_deleted = FALSE
+         WHERE k.user_id = ?
+           AND ((s.entry_date BETWEEN ? AND ?) OR (t.entry_date BETWEEN ? AND ?))
+         ORDER BY k.source_id, k.target_id"
+    )
+    .bind(user.id)
+    .bind(&from).bind(&to)
+    .bind(&from).bind(&to)
+    .fetch_all(&pool)
+    .await?;
+
+    let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        "SELECT id, entry_date, entry_time, COALESCE(category, 'General') as category, mood,
+                (entry_date BETWEEN "
+    );
+    qb.push_bind(&from).push(" AND ").push_bind(&to).push(") as in_range, content FROM log_entries WHERE user_id = ");
+    qb.push_bind(user.id).push(" AND is_deleted = FALSE AND (id IN (");
+    let mut ids = qb.separated(", ");
+    ids.push("NULL"); // Supaya IN () tidak kosong kalau belum ada edge
+    for id in edges.iter().flat_map(|e| [e.source, e.target]).collect::<BTreeSet<_>>() {
+        ids.push_bind(id);
+    }
+    qb.push(")");
+    if query.include_isolated.unwrap_or(false) {
+        qb.push(" OR entry_date BETWEEN ").push_bind(&from).push(" AND ").push_bind(&to);
+    }
+    qb.push(") ORDER BY entry_date ASC, entry_time ASC");
+
+    let nodes = qb.build_query_as::<GraphNode>().fetch_all(&pool).await?
+        .into_iter()
+        .map(|mut n| { n.label = excerpt(&n.content); n })
+        .collect();
+
+    Ok(Json(Graph { from, to, nodes, edges }))
+}
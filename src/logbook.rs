@@ -1,12 +1,31 @@
 use axum::{
-    extract::{Path, State, Query},
+    extract::{FromRequestParts, Path, State, Query},
+    http::{header, request::Parts, StatusCode},
     response::Json,
-    Json as JsonBody,
-    http::StatusCode,
+    Extension,
 };
 use sqlx::SqlitePool;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::auth::AuthUser;
+use crate::config::LogbookConfig;
+use crate::error::{AppError, AppResult, FieldError, JsonBody};
+
+// Nilai bawaan; tambahan lain lewat [logbook] extra_sources / extra_moods di config
+const SOURCES: &[&str] = &["Manual", "GitHub", "Telegram-Bot", "Import", "Obsidian", "Day One"];
+const MOODS: &[&str] = &["Neutral", "Productive", "Tired", "Excited", "Stressed"];
+
+static RULES: OnceLock<LogbookConfig> = OnceLock::new();
+
+// Dipanggil sekali di main setelah config dibaca
+pub fn init(config: LogbookConfig) {
+    let _ = RULES.set(config);
+}
+
+fn rules() -> &'static LogbookConfig {
+    RULES.get_or_init(LogbookConfig::default)
+}
 
 // --- MODEL DATA (Sesuai Database) ---
 #[derive(Serialize, sqlx::FromRow)]
@@ -16,12 +35,130 @@ pub struct LogEntry {
     pub entry_date: String,     // Format YYYY-MM-DD
     pub entry_time: String,     // Format HH:MM:SS
     pub created_at: String,     // Timestamp sistem
-    pub tags: Option<String>,   // JSON String: "['ide', 'kerja']"
+    #[sqlx(rename = "tags")]
+    #[serde(skip)]
+    pub tags_json: Option<String>, // Kolom DB: JSON array, misal ["ide","kerja"]
+    #[sqlx(skip)]
+    pub tags: Vec<String>,         // Yang dikirim ke client (API v2)
+    pub category: String,
+    pub mood: Option<String>,
+    pub source: String,
+}
+
+impl LogEntry {
+    fn hydrate(mut self) -> Self {
+        self.tags = parse_tags(self.tags_json.as_deref());
+        self
+    }
+}
+
+// Bentuk lama (API v1): tags berupa string JSON yang harus di-decode dua kali oleh client
+#[derive(Serialize)]
+pub struct LegacyLogEntry {
+    pub id: i64,
+    pub content: String,
+    pub entry_date: String,
+    pub entry_time: String,
+    pub created_at: String,
+    pub tags: Option<String>,
+    pub category: String,
+    pub mood: Option<String>,
+    pub source: String,
+}
+
+impl From<LogEntry> for LegacyLogEntry {
+    fn from(entry: LogEntry) -> Self {
+        LegacyLogEntry {
+            tags: Some(serde_json::to_string(&entry.tags).unwrap_or("[]".to_string())),
+            id: entry.id,
+            content: entry.content,
+            entry_date: entry.entry_date,
+            entry_time: entry.entry_time,
+            created_at: entry.created_at,
+            category: entry.category,
+            mood: entry.mood,
+            source: entry.source,
+        }
+    }
+}
+
+// ==========================================
+// VERSI API (?api_version=2 atau header X-Api-Version: 2)
+// ==========================================
+
+// v1 (default): bentuk lama, tags = string JSON, supaya client lama tetap jalan setelah upgrade.
+// v2 (opt-in): tags = array; client baru wajib kirim api_version=2.
+// Berlaku untuk GET/POST /api/logs; detail log (GET /api/logs/{id}) selalu berbentuk v2.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+pub const API_VERSION_HEADER: &str = "x-api-version";
+
+#[derive(Deserialize)]
+struct ApiVersionQuery {
+    api_version: Option<String>,
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ApiVersion {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let from_query = Query::<ApiVersionQuery>::try_from_uri(&parts.uri).ok().and_then(|q| q.0.api_version);
+        let from_header = parts.headers.get(API_VERSION_HEADER).and_then(|v| v.to_str().ok()).map(str::to_string);
+
+        match from_query.or(from_header).as_deref().map(str::trim) {
+            None | Some("") | Some("1") => Ok(ApiVersion::V1),
+            Some("2") => Ok(ApiVersion::V2),
+            Some(other) => Err(AppError::bad_request(format!("api_version '{}' tidak dikenal, pilih 1 atau 2", other))
+                .with_code("invalid_api_version")),
+        }
+    }
+}
+
+// Respons log sesuai versi yang diminta client
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Versioned<T, L> {
+    Current(T),
+    Legacy(L),
+}
+
+fn versioned_list(entries: Vec<LogEntry>, version: ApiVersion) -> Versioned<Vec<LogEntry>, Vec<LegacyLogEntry>> {
+    match version {
+        ApiVersion::V2 => Versioned::Current(entries),
+        ApiVersion::V1 => Versioned::Legacy(entries.into_iter().map(LegacyLogEntry::from).collect()),
+    }
+}
+
+// Satu log lengkap dengan relasinya (GET /api/logs/{id}); tags sudah berupa array
+#[derive(Serialize)]
+pub struct LogDetail {
+    pub id: i64,
+    pub content: String,
+    pub entry_date: String,
+    pub entry_time: String,
+    pub created_at: String,
+    pub tags: Vec<String>,
     pub category: String,
     pub mood: Option<String>,
     pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub html: Option<String>,                          // Hanya kalau diminta (?html=true)
+    pub attachments: Vec<crate::attachments::Attachment>,
+    pub links: Vec<crate::links::LinkedEntry>,         // Log yang dirujuk lewat [[entry:123]]
+    pub backlinks: Vec<crate::links::LinkedEntry>,     // Log lain yang merujuk ke log ini
 }
 
+#[derive(Deserialize)]
+pub struct LogDetailQuery {
+    pub html: Option<bool>, // Sertakan render HTML (sudah disanitasi) dari isi Markdown
+}
+
+const LOG_COLUMNS: &str = "id, content, entry_date, entry_time, CAST(created_at AS TEXT) as created_at, tags, category, mood, source";
+
 // --- INPUT DARI USER (Frontend) ---
 #[derive(Deserialize)]
 pub struct CreateLogRequest {
@@ -32,12 +169,96 @@ pub struct CreateLogRequest {
     pub category: Option<String>,
     pub mood: Option<String>,
     pub source: Option<String>, // 'Manual' defaultnya
+    #[serde(default)]
+    pub template_id: Option<i64>, // Tag & kategori default diambil dari template ini
+}
+
+impl CreateLogRequest {
+    // Cek semua field sekaligus & rapikan formatnya (tanggal/jam dinormalisasi, ejaan enum diseragamkan)
+    pub fn validate(&mut self) -> AppResult<()> {
+        let rules = rules();
+        let mut errors = Vec::new();
+
+        self.content = self.content.trim().to_string();
+        let length = self.content.chars().count();
+        if length == 0 {
+            errors.push(FieldError::new("content", "Isi catatan tidak boleh kosong"));
+        } else if length > rules.max_content_length {
+            errors.push(FieldError::new("content", format!("Maksimal {} karakter, dapat {}", rules.max_content_length, length)));
+        }
+
+        match chrono::NaiveDate::parse_from_str(self.entry_date.trim(), "%Y-%m-%d") {
+            Ok(date) => self.entry_date = date.format("%Y-%m-%d").to_string(),
+            Err(_) => errors.push(FieldError::new("entry_date", format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", self.entry_date))),
+        }
+
+        // Input jam dari browser kadang tanpa detik
+        let time = self.entry_time.trim();
+        match chrono::NaiveTime::parse_from_str(time, "%H:%M:%S").or_else(|_| chrono::NaiveTime::parse_from_str(time, "%H:%M")) {
+            Ok(t) => self.entry_time = t.format("%H:%M:%S").to_string(),
+            Err(_) => errors.push(FieldError::new("entry_time", format!("Format jam harus HH:MM:SS, dapat '{}'", self.entry_time))),
+        }
+
+        if let Some(source) = &self.source {
+            match canonical(source, SOURCES, &rules.extra_sources) {
+                Some(value) => self.source = Some(value),
+                None => errors.push(FieldError::new("source", format!("Source '{}' tidak dikenal, pilih dari: {}", source, allowed(SOURCES, &rules.extra_sources)))),
+            }
+        }
+
+        // Mood kosong dianggap tidak diisi
+        match self.mood.as_deref().map(str::trim) {
+            None | Some("") => self.mood = None,
+            Some(mood) => match known_mood(mood) {
+                Some(value) => self.mood = Some(value),
+                None => errors.push(FieldError::new("mood", format!("Mood '{}' tidak dikenal, pilih dari: {}", mood, allowed_moods()))),
+            },
+        }
+
+        self.tags = self.tags.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+
+        if errors.is_empty() { Ok(()) } else { Err(AppError::validation(errors)) }
+    }
+}
+
+// Cocokkan tanpa peduli huruf besar/kecil, simpan dengan ejaan resminya
+fn canonical(value: &str, builtin: &[&str], extra: &[String]) -> Option<String> {
+    let value = value.trim();
+    builtin.iter().copied()
+        .chain(extra.iter().map(String::as_str))
+        .find(|known| known.eq_ignore_ascii_case(value))
+        .map(str::to_string)
+}
+
+// Batas panjang isi log (template juga ikut batas ini)
+pub fn max_content_length() -> usize {
+    rules().max_content_length
+}
+
+// Ejaan resmi sebuah source kalau dikenal (bawaan maupun dari config)
+pub fn known_source(value: &str) -> Option<String> {
+    canonical(value, SOURCES, &rules().extra_sources)
+}
+
+// Ejaan resmi sebuah mood kalau dikenal (bawaan maupun dari config)
+pub fn known_mood(value: &str) -> Option<String> {
+    canonical(value, MOODS, &rules().extra_moods)
+}
+
+pub fn allowed_moods() -> String {
+    allowed(MOODS, &rules().extra_moods)
+}
+
+fn allowed(builtin: &[&str], extra: &[String]) -> String {
+    builtin.iter().copied().chain(extra.iter().map(String::as_str)).collect::<Vec<_>>().join(", ")
 }
 
 #[derive(Deserialize)]
 pub struct LogFilter {
     pub date: Option<String>, // Filter per tanggal
     pub tag: Option<String>,  // Filter per tag
+    pub category: Option<String>,          // Filter per kategori (nama)
+    pub include_subcategories: Option<bool>, // Default true: sub-kategori ikut
 }
 
 // --- HANDLERS (Fungsi API) ---
@@ -45,73 +266,398 @@ pub struct LogFilter {
 // 1. GET ALL LOGS (Bisa filter by date)
 pub async fn get_logs(
     State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    version: ApiVersion,
     Query(params): Query<LogFilter>,
-) -> Result<Json<Vec<LogEntry>>, StatusCode> {
+) -> AppResult<Json<Versioned<Vec<LogEntry>, Vec<LegacyLogEntry>>>> {
     
     // Query dasar: Ambil yang TIDAK dihapus (Soft Delete)
-    let mut sql = "SELECT id, content, entry_date, entry_time, CAST(created_at AS TEXT) as created_at, tags, category, mood, source FROM log_entries WHERE is_deleted = FALSE".to_string();
+    let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        format!("SELECT {} FROM log_entries WHERE is_deleted = FALSE AND user_id = ", LOG_COLUMNS)
+    );
+    qb.push_bind(user.id);
     
     // Filter tanggal (jika ada)
     if let Some(d) = params.date {
-        sql.push_str(&format!(" AND entry_date = '{}'", d));
+        qb.push(" AND entry_date = ").push_bind(d);
+    }
+
+    // Filter tag (tags disimpan sebagai JSON array)
+    if let Some(t) = params.tag {
+        qb.push(" AND EXISTS (SELECT 1 FROM json_each(log_entries.tags) WHERE json_each.value = ")
+            .push_bind(t)
+            .push(")");
+    }
+
+    // Filter kategori, termasuk turunannya kecuali diminta sebaliknya
+    if let Some(c) = params.category.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()) {
+        if params.include_subcategories.unwrap_or(true) {
+            qb.push(" AND (category = ").push_bind(c.clone()).push(" COLLATE NOCASE OR category COLLATE NOCASE IN (")
+                .push("WITH RECURSIVE subtree(id, name) AS (SELECT id, name FROM categories WHERE user_id = ").push_bind(user.id)
+                .push(" AND name = ").push_bind(c)
+                .push(" UNION SELECT cat.id, cat.name FROM categories cat JOIN subtree s ON cat.parent_id = s.id) SELECT name FROM subtree))");
+        } else {
+            qb.push(" AND category = ").push_bind(c).push(" COLLATE NOCASE");
+        }
     }
     
     // Urutkan dari yang terbaru (waktu entry, bukan waktu input)
-    sql.push_str(" ORDER BY entry_date DESC, entry_time DESC");
+    qb.push(" ORDER BY entry_date DESC, entry_time DESC");
 
-    let logs = sqlx::query_as::<_, LogEntry>(&sql)
+    let logs = qb.build_query_as::<LogEntry>()
         .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            eprintln!("❌ Error fetch logs: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .await?
+        .into_iter()
+        .map(LogEntry::hydrate)
+        .collect();
+
+    Ok(Json(versioned_list(logs, version)))
+}
+
+// Satu log milik user (yang sudah di-trash dianggap tidak ada)
+pub async fn fetch_log(pool: &SqlitePool, user_id: i64, id: i64) -> Result<Option<LogEntry>, sqlx::Error> {
+    sqlx::query_as::<_, LogEntry>(&format!(
+        "SELECT {} FROM log_entries WHERE id = ? AND user_id = ? AND is_deleted = FALSE", LOG_COLUMNS
+    ))
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await
+    .map(|entry| entry.map(LogEntry::hydrate))
+}
 
-    Ok(Json(logs))
+// 2. GET ONE LOG
+pub async fn get_log(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    Query(query): Query<LogDetailQuery>,
+) -> AppResult<Json<LogDetail>> {
+    let entry = fetch_log(&pool, user.id, id).await?
+        .ok_or_else(|| AppError::not_found(format!("Log #{} tidak ditemukan", id)))?;
+
+    Ok(Json(LogDetail {
+        html: query.html.unwrap_or(false).then(|| crate::markdown::render(&entry.content)),
+        tags: entry.tags,
+        attachments: crate::attachments::list_for_entry(&pool, user.id, id).await?,
+        links: crate::links::outgoing(&pool, user.id, id).await?,
+        backlinks: crate::links::backlinks(&pool, user.id, id).await?,
+        id: entry.id,
+        content: entry.content,
+        entry_date: entry.entry_date,
+        entry_time: entry.entry_time,
+        created_at: entry.created_at,
+        category: entry.category,
+        mood: entry.mood,
+        source: entry.source,
+    }))
 }
 
-// 2. CREATE NEW LOG
+// 3. CREATE NEW LOG (balikin entry lengkap supaya UI gak perlu refetch)
 pub async fn create_log(
     State(pool): State<SqlitePool>,
-    JsonBody(payload): JsonBody<CreateLogRequest>,
-) -> Result<Json<String>, StatusCode> {
-    
+    Extension(user): Extension<AuthUser>,
+    version: ApiVersion,
+    JsonBody(mut payload): JsonBody<CreateLogRequest>,
+) -> AppResult<(StatusCode, [(header::HeaderName, String); 1], Json<Versioned<LogEntry, LegacyLogEntry>>)> {
+    if let Some(template_id) = payload.template_id {
+        crate::templates::apply_defaults(&pool, user.id, template_id, &mut payload).await?;
+    }
+    payload.validate()?;
+    let id = insert_log(&pool, user.id, payload).await?;
+
+    let entry = fetch_log(&pool, user.id, id).await?
+        .ok_or_else(|| AppError::internal("Error logbook", format!("log #{} hilang setelah dibuat", id)))?;
+
+    let body = match version {
+        ApiVersion::V2 => Versioned::Current(entry),
+        ApiVersion::V1 => Versioned::Legacy(entry.into()),
+    };
+    Ok((StatusCode::CREATED, [(header::LOCATION, format!("/api/logs/{}", id))], Json(body)))
+}
+
+// Dipakai handler & integrasi (GitHub, dll) supaya format insert-nya seragam
+pub async fn insert_log(pool: &SqlitePool, user_id: i64, mut payload: CreateLogRequest) -> Result<i64, sqlx::Error> {
+    // Kategori disimpan dengan ejaan resmi (kategori baru otomatis dibuat)
+    payload.category = Some(crate::categories::resolve(pool, user_id, payload.category.as_deref()).await?);
+    let id = insert_entry(pool, user_id, &payload).await?;
+    crate::links::sync_links(pool, user_id, id, &payload.content).await?;
+
+    // Cari tugas & tanggal di isi log (usulan, tetap butuh konfirmasi user)
+    crate::actions::spawn_auto_extract(pool.clone(), user_id, id, payload.source.as_deref().unwrap_or("Manual"));
+
+    // Kabari target webhook (Discord, dll)
+    crate::webhooks::notify(pool, user_id, "entry_created", serde_json::json!({
+        "id": id,
+        "content": payload.content,
+        "entry_date": payload.entry_date,
+        "entry_time": payload.entry_time,
+        "tags": payload.tags,
+        "category": payload.category.as_deref().unwrap_or("General"),
+        "mood": payload.mood,
+        "source": payload.source.as_deref().unwrap_or("Manual"),
+    })).await;
+
+    Ok(id)
+}
+
+// Insert mentah tanpa notifikasi; import massal pakai ini di dalam transaksi
+pub async fn insert_entry<'e, E>(executor: E, user_id: i64, payload: &CreateLogRequest) -> Result<i64, sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
     // Convert array tags ke JSON String
     let tags_json = serde_json::to_string(&payload.tags).unwrap_or("[]".to_string());
-    let category = payload.category.unwrap_or("General".to_string());
-    let source = payload.source.unwrap_or("Manual".to_string());
+    let category = payload.category.as_deref().unwrap_or("General");
+    let source = payload.source.as_deref().unwrap_or("Manual");
 
-    sqlx::query(
-        "INSERT INTO log_entries (content, entry_date, entry_time, tags, category, mood, source) 
-         VALUES (?, ?, ?, ?, ?, ?, ?)"
+    let result = sqlx::query(
+        "INSERT INTO log_entries (user_id, content, entry_date, entry_time, tags, category, mood, source) 
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
     )
-    .bind(payload.content)
-    .bind(payload.entry_date)
-    .bind(payload.entry_time)
+    .bind(user_id)
+    .bind(&payload.content)
+    .bind(&payload.entry_date)
+    .bind(&payload.entry_time)
     .bind(tags_json)
     .bind(category)
-    .bind(payload.mood)
+    .bind(&payload.mood)
     .bind(source)
-    .execute(&pool)
-    .await
-    .map_err(|e| {
-        eprintln!("❌ Error create log: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .execute(executor)
+    .await?;
 
-    Ok(Json("Log berhasil dicatat".to_string()))
+    Ok(result.last_insert_rowid())
 }
 
-// 3. DELETE LOG (Soft Delete)
+// 4. DELETE LOG (Soft Delete)
 pub async fn delete_log(
     State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
     Path(id): Path<i64>,
-) -> Result<Json<String>, StatusCode> {
-    sqlx::query("UPDATE log_entries SET is_deleted = TRUE WHERE id = ?")
+) -> AppResult<Json<String>> {
+    let result = sqlx::query("UPDATE log_entries SET is_deleted = TRUE WHERE id = ? AND user_id = ?")
         .bind(id)
+        .bind(user.id)
         .execute(&pool)
-        .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Log #{} tidak ditemukan", id)));
+    }
 
     Ok(Json("Log dihapus (disimpan di trash)".to_string()))
-}
\ No newline at end of file
+}
+
+// 5. KOSONGKAN TRASH (hapus permanen + lampirannya)
+pub async fn purge_trash(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<String>> {
+    let mut tx = pool.begin().await?;
+
+    // Referensi integrasi tetap disimpan (supaya item yang sama gak di-import ulang), cukup dilepas
+    sqlx::query(
+        "UPDATE external_refs SET log_entry_id = NULL
+         WHERE log_entry_id IN (SELECT id FROM log_entries WHERE user_id = ? AND is_deleted = TRUE)"
+    )
+    .bind(user.id)
+    .execute(&mut *tx)
+    .await?;
+
+    // Baris attachments & entry_links ikut terhapus (ON DELETE CASCADE). File lampirannya
+    // dibersihkan belakangan oleh attachments::cleanup_orphans.
+    let purged = sqlx::query("DELETE FROM log_entries WHERE user_id = ? AND is_deleted = TRUE")
+        .bind(user.id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    tx.commit().await?;
+
+    Ok(Json(format!("{} log dihapus permanen dari trash", purged)))
+}
+
+// ==========================================
+// PARSING & PERBAIKAN TAG
+// ==========================================
+
+// Satu-satunya pembaca kolom tags (log, template, export, bot). Bentuk resminya JSON array
+// ["a","b"], tapi data lama/eksternal kadang menyimpan "['a', 'b']", "a, b", JSON yang
+// di-encode dua kali, atau array berisi angka. Semua dibaca sebisanya jadi daftar tag.
+pub fn parse_tags(raw: Option<&str>) -> Vec<String> {
+    let raw = raw.map(str::trim).unwrap_or("");
+    if raw.is_empty() || raw.eq_ignore_ascii_case("null") {
+        return Vec::new();
+    }
+
+    let candidates: Vec<String> = match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(serde_json::Value::Array(items)) => items.into_iter()
+            .filter_map(|item| match item {
+                serde_json::Value::String(s) => Some(s),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                serde_json::Value::Bool(b) => Some(b.to_string()),
+                _ => None,
+            })
+            .collect(),
+        // JSON string berisi JSON lagi (double-encode) -> baca isinya
+        Ok(serde_json::Value::String(inner)) if inner.trim() != raw => return parse_tags(Some(&inner)),
+        Ok(serde_json::Value::Null) => Vec::new(),
+        _ => raw.trim_start_matches('[').trim_end_matches(']')
+            .split(',')
+            .map(|t| t.trim().trim_matches(|c| c == '\'' || c == '"').to_string())
+            .collect(),
+    };
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in candidates.into_iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+#[derive(Serialize, Default)]
+pub struct TagRepairReport {
+    pub scanned: usize,
+    pub repaired: usize,
+    pub dry_run: bool,
+    pub samples: Vec<TagRepairSample>, // Maksimal REPAIR_SAMPLES contoh
+}
+
+#[derive(Serialize)]
+pub struct TagRepairSample {
+    pub id: i64,
+    pub before: Option<String>,
+    pub after: String,
+}
+
+const REPAIR_SAMPLES: usize = 50;
+
+// Tulis ulang kolom tags yang tidak sama dengan bentuk kanonik ["a","b"] (termasuk trash)
+pub async fn repair_tags(pool: &SqlitePool, dry_run: bool) -> Result<TagRepairReport, sqlx::Error> {
+    let rows: Vec<(i64, Option<String>)> = sqlx::query_as("SELECT id, tags FROM log_entries").fetch_all(pool).await?;
+    let mut report = TagRepairReport { scanned: rows.len(), dry_run, ..Default::default() };
+
+    let mut tx = pool.begin().await?;
+    for (id, before) in rows {
+        let after = serde_json::to_string(&parse_tags(before.as_deref())).unwrap_or("[]".to_string());
+        if before.as_deref() == Some(after.as_str()) {
+            continue;
+        }
+
+        if !dry_run {
+            sqlx::query("UPDATE log_entries SET tags = ? WHERE id = ?")
+                .bind(&after)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        report.repaired += 1;
+        if report.samples.len() < REPAIR_SAMPLES {
+            report.samples.push(TagRepairSample { id, before, after });
+        }
+    }
+    tx.commit().await?;
+
+    Ok(report)
+}
+
+#[derive(Deserialize)]
+pub struct RepairQuery {
+    pub dry_run: Option<bool>,
+}
+
+// POST /api/admin/repair-tags?dry_run=true (admin; juga jalan otomatis saat startup)
+pub async fn repair_tags_now(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<RepairQuery>,
+) -> AppResult<Json<TagRepairReport>> {
+    crate::users::require_admin(&user, "memperbaiki data tag")?;
+    Ok(Json(repair_tags(&pool, query.dry_run.unwrap_or(false)).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(raw: &str) -> Vec<String> {
+        parse_tags(Some(raw))
+    }
+
+    #[test]
+    fn parses_canonical_json_array() {
+        assert_eq!(tags(r#"["kerja","ide"]"#), ["kerja", "ide"]);
+        assert_eq!(tags(r#"[" kerja ", "", "ide", "kerja"]"#), ["kerja", "ide"]);
+    }
+
+    #[test]
+    fn parses_non_string_json_items() {
+        assert_eq!(tags(r#"["v2", 2024, true, null, {"a": 1}]"#), ["v2", "2024", "true"]);
+    }
+
+    #[test]
+    fn parses_double_encoded_json() {
+        assert_eq!(tags(r#""[\"kerja\",\"ide\"]""#), ["kerja", "ide"]);
+    }
+
+    #[test]
+    fn parses_python_style_and_comma_lists() {
+        assert_eq!(tags("['kerja', 'ide']"), ["kerja", "ide"]);
+        assert_eq!(tags("kerja, ide ,kerja"), ["kerja", "ide"]);
+        assert_eq!(tags("kerja"), ["kerja"]);
+    }
+
+    #[test]
+    fn empty_values_have_no_tags() {
+        assert!(parse_tags(None).is_empty());
+        for raw in ["", "  ", "null", "NULL", "[]", "\"\"", "[,]"] {
+            assert!(tags(raw).is_empty(), "{:?}", raw);
+        }
+    }
+
+    async fn setup() -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        sqlx::query("CREATE TABLE log_entries (id INTEGER PRIMARY KEY, tags TEXT, is_deleted BOOLEAN DEFAULT FALSE)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (raw, deleted) in [(Some(r#"["ok"]"#), false), (Some("a, b"), false), (None, false), (Some("['lama']"), true)] {
+            sqlx::query("INSERT INTO log_entries (tags, is_deleted) VALUES (?, ?)").bind(raw).bind(deleted).execute(&pool).await.unwrap();
+        }
+        pool
+    }
+
+    async fn stored_tags(pool: &SqlitePool) -> Vec<Option<String>> {
+        sqlx::query_scalar("SELECT tags FROM log_entries ORDER BY id").fetch_all(pool).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn repair_dry_run_changes_nothing() {
+        let pool = setup().await;
+        let before = stored_tags(&pool).await;
+
+        let report = repair_tags(&pool, true).await.unwrap();
+        assert!(report.dry_run);
+        assert_eq!((report.scanned, report.repaired), (4, 3));
+        assert_eq!(stored_tags(&pool).await, before);
+    }
+
+    #[tokio::test]
+    async fn repair_rewrites_to_canonical_json_including_trash() {
+        let pool = setup().await;
+
+        let report = repair_tags(&pool, false).await.unwrap();
+        let ids: Vec<i64> = report.samples.iter().map(|s| s.id).collect();
+        assert_eq!(ids, [2, 3, 4]);
+        assert_eq!(report.samples[0].before.as_deref(), Some("a, b"));
+        assert_eq!(report.samples[0].after, r#"["a","b"]"#);
+
+        let expected = [r#"["ok"]"#, r#"["a","b"]"#, "[]", r#"["lama"]"#].map(|t| Some(t.to_string()));
+        assert_eq!(stored_tags(&pool).await, expected);
+
+        // Sudah kanonik semua, jalan kedua tidak mengubah apa-apa
+        assert_eq!(repair_tags(&pool, false).await.unwrap().repaired, 0);
+    }
+}
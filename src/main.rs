@@ -1,17 +1,43 @@
 use axum::{
+    extract::DefaultBodyLimit,
+    http::{header, Method},
+    middleware,
     routing::{get, post},
     Router
 };
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
-use std::time::Duration;
+use sqlx::sqlite::SqlitePoolOptions;
 use tower_http::cors::CorsLayer;
+use axum_server::tls_rustls::RustlsConfig;
 use std::env;
 
 mod logbook; // <--- BARU
 mod personas;
 mod ai_features; // <--- BARU
 mod github_sync;
+mod integrations; // Trait + registry semua integrasi
 mod integrations_api; // <--- Baru
+mod telegram;
+mod notion_sync;
+mod webhooks; // Notifikasi keluar (Discord & webhook umum)
+mod db;
+mod error; // AppError: format error JSON semua endpoint
+mod config; // Konfigurasi server (noty.toml + env)
+mod ui; // Sajikan UI React dari binary
+mod crypto; // Enkripsi API key & token di database
+mod auth; // Login, session & API token
+mod users; // Multi-user: kepemilikan data & kelola user
+mod export; // Export logbook (Markdown, JSON, CSV)
+mod import; // Import dari dump Noty, Obsidian & Day One
+mod backup; // Backup terjadwal & restore noty.db
+mod attachments; // Lampiran file/gambar per log
+mod reminders; // Reminder, jadwal berulang & inbox notifikasi
+mod actions; // Usulan tugas/reminder hasil ekstraksi AI dari log
+mod stats; // Statistik & analitik mood untuk dashboard
+mod goals; // Target menulis, streak & nudge akhir hari
+mod categories; // Kategori log: warna, ikon & hierarki
+mod templates; // Template log dengan placeholder
+mod links; // Link antar log [[entry:123]] & backlink
+mod markdown; // Render Markdown log jadi HTML tersanitasi
 // Kita akan buat modul baru nanti untuk handling logbook
 // mod logbook; 
 mod settings;
@@ -21,17 +47,36 @@ mod settings;
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 1. Load Environment Variables
     dotenvy::dotenv().ok();
-    tracing_subscriber::fmt::init();
+
+    // Config salah = langsung berhenti dengan pesan yang jelas
+    let config = config::load().unwrap_or_else(|e| {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    });
+    tracing_subscriber::fmt().with_max_level(config.log_level()).init();
+    logbook::init(config.logbook.clone());
+    backup::init(config.backup.clone());
+    attachments::init(config.attachments.clone());
+    auth::init(config.server.tls.is_some());
 
     println!("📚 Noty Logbook System Initializing...");
 
+    // Master key untuk secret di database (env NOTY_MASTER_KEY atau keyfile)
+    crypto::init()?;
+
+    // CLI: `noty restore <file>` -> ganti database dengan backup lalu keluar (server harus mati)
+    if env::args().nth(1).as_deref() == Some("restore") {
+        let file = env::args().nth(2).ok_or("Pakai: noty restore <file-backup.db>")?;
+        backup::restore(&config.database.url, std::path::Path::new(&file)).await?;
+        return Ok(());
+    }
+
     // 2. Setup Database Connection
-    let db_url = env::var("DATABASE_URL").unwrap_or("sqlite://noty.db?mode=rwc".to_string());
     let pool = SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&db_url)
+        .max_connections(config.database.max_connections)
+        .connect(&config.database.url)
         .await
-        .expect("❌ Gagal membuat file database noty.db");
+        .map_err(|e| format!("Gagal membuka database {}: {}", config.database.url, e))?;
 
     // ==========================================
     // 3. SKEMA DATABASE BARU (The Foundation)
@@ -109,71 +154,138 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );"
     ).execute(&pool).await?;
 
+    // E. User, Session & API Token (+ user_id di semua tabel data)
+    auth::init_schema(&pool).await?;
+    users::init_schema(&pool).await?;
+
+    // F. Status & Referensi Eksternal Integrasi
+    integrations::init_schema(&pool).await?;
+
+    // G. Log Pengiriman Webhook
+    webhooks::init_schema(&pool).await?;
+
+    // H. Lampiran (file & gambar) per log
+    attachments::init_schema(&pool).await?;
+
+    // I. Reminder & inbox notifikasi
+    reminders::init_schema(&pool).await?;
+
+    // J. Usulan tugas hasil ekstraksi AI dari log
+    actions::init_schema(&pool).await?;
+
+    // K. Target menulis, hari kerja & libur
+    goals::init_schema(&pool).await?;
+
+    // L. Kategori (sebelum seeding default per user di bawah)
+    categories::init_schema(&pool).await?;
+
+    // M. Template log
+    templates::init_schema(&pool).await?;
+
+    // N. Link antar log
+    links::init_schema(&pool).await?;
+
+    // Tandai versi skema (dicek saat restore backup)
+    db::set_schema_version(&pool).await?;
+
     println!("✅ Struktur Database Logbook Berhasil Dibangun.");
 
+    // Kolom tags lama yang bukan JSON array valid dirapikan
+    let repaired = logbook::repair_tags(&pool, false).await?;
+    if repaired.repaired > 0 {
+        println!("🏷️ {} log dengan format tags rusak diperbaiki.", repaired.repaired);
+    }
+
+    // Secret lama yang masih plaintext langsung dienkripsi
+    let encrypted = crypto::encrypt_existing(&pool).await?;
+    if encrypted > 0 {
+        println!("🔒 {} secret lama dienkripsi.", encrypted);
+    }
+
+    // CLI: `noty rotate-key` -> ganti master key lalu keluar
+    if env::args().nth(1).as_deref() == Some("rotate-key") {
+        crypto::rotate_key(&pool).await?;
+        return Ok(());
+    }
+
     // ==========================================
     // 4. SEEDING DATA (Isi Data Awal)
     // ==========================================
 
-    // Seed Settings Default
-    let settings_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM app_settings").fetch_one(&pool).await?;
-    if settings_count == 0 {
-        sqlx::query("INSERT INTO app_settings (username) VALUES ('User')").execute(&pool).await?;
+    // Tiap user punya settings & persona sendiri (user baru di-seed saat dibuat)
+    let user_ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM users").fetch_all(&pool).await?;
+    for user_id in user_ids {
+        users::ensure_defaults(&pool, user_id).await?;
     }
 
-    // Seed 3 Persona Dasar (Sesuai Request)
-    let persona_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ai_personas").fetch_one(&pool).await?;
-    if persona_count == 0 {
-        // 1. The Scribe (Pencatat)
-        sqlx::query(
-            "INSERT INTO ai_personas (name, description, system_prompt, is_active) VALUES (?, ?, ?, ?)"
-        )
-        .bind("The Scribe")
-        .bind("Pencatat logbook yang rapi dan formal.")
-        .bind("Kamu adalah Scribe. Tugasmu adalah merapikan input pengguna menjadi entri logbook yang terstruktur, jelas, dan kronologis. Jangan banyak bicara, fokus pada akurasi pencatatan.")
-        .bind(true) // Default active
-        .execute(&pool).await?;
-
-        // 2. Discussion Partner (Teman Diskusi)
-        sqlx::query(
-            "INSERT INTO ai_personas (name, description, system_prompt, is_active) VALUES (?, ?, ?, ?)"
-        )
-        .bind("Discussion Partner")
-        .bind("Teman brainstorming dan curhat.")
-        .bind("Kamu adalah Partner diskusi. Dengarkan ide, keluh kesah, atau rencana pengguna. Berikan feedback konstruktif, pertanyaan pancingan, dan dukungan emosional. Jadilah teman yang cerdas.")
-        .bind(false)
-        .execute(&pool).await?;
-
-        // 3. The Helper (Asisten)
-        sqlx::query(
-            "INSERT INTO ai_personas (name, description, system_prompt, is_active) VALUES (?, ?, ?, ?)"
-        )
-        .bind("The Helper")
-        .bind("Asisten manajemen waktu dan pengingat.")
-        .bind("Kamu adalah Helper. Tugasmu mengingatkan jadwal, mencari data di log lama, dan memastikan tidak ada tugas yang terlewat. Kamu sangat teliti terhadap waktu dan deadline.")
-        .bind(false)
-        .execute(&pool).await?;
-        
-        println!("🌱 Persona AI Dasar (Scribe, Partner, Helper) telah ditanam.");
-    }
+    // --- BACKGROUND TASK: SYNC SEMUA INTEGRASI ---
+    integrations::spawn_pollers(pool.clone());
+
+    // --- BACKGROUND TASK: WEBHOOK KELUAR ---
+    tokio::spawn(webhooks::start_delivery_worker(pool.clone()));
+    tokio::spawn(webhooks::start_daily_summary(pool.clone()));
+
+    // --- BACKGROUND TASK: BACKUP TERJADWAL ---
+    tokio::spawn(backup::start_scheduler(pool.clone()));
+
+    // --- BACKGROUND TASK: BERSIHKAN FILE LAMPIRAN YATIM ---
+    tokio::spawn(attachments::start_cleanup(pool.clone()));
 
-    // --- BACKGROUND TASK: GITHUB SYNC ---
-let pool_for_sync = pool.clone();
-tokio::spawn(async move {
-    github_sync::start_github_polling(pool_for_sync).await;
-});
+    // --- BACKGROUND TASK: REMINDER JATUH TEMPO ---
+    tokio::spawn(reminders::start_scheduler(pool.clone()));
+
+    // --- BACKGROUND TASK: NUDGE TARGET MENULIS ---
+    tokio::spawn(goals::start_nudges(pool.clone()));
 
     // ==========================================
     // 5. SERVER SETUP
     // ==========================================
     
-let app = Router::new()
-        .route("/", get(|| async { "Noty Logbook System v2.0 Online 🟢" }))
-        
+    // Origin UI yang boleh kirim cookie session (lihat config.rs)
+    let cors = CorsLayer::new()
+        .allow_origin(config.cors_origins())
+        .allow_credentials(true)
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+
+    // Semua route di sini wajib login (session cookie atau Bearer token)
+    let protected = Router::new()
         // --- API LOGBOOK ---
         .route("/api/logs", get(logbook::get_logs).post(logbook::create_log))
         // GANTI :id JADI {id}
-        .route("/api/logs/{id}", axum::routing::delete(logbook::delete_log)) 
+        .route("/api/logs/{id}", get(logbook::get_log).delete(logbook::delete_log)) 
+        .route("/api/logs/trash", axum::routing::delete(logbook::purge_trash))
+        .route("/api/logs/graph", get(links::graph))
+        .route("/api/logs/{id}/attachments", get(attachments::list).post(attachments::upload).layer(DefaultBodyLimit::max(attachments::max_upload_bytes())))
+        .route("/api/attachments/{id}", get(attachments::download).delete(attachments::delete))
+        .route("/api/attachments/{id}/thumbnail", get(attachments::thumbnail))
+        .route("/api/export", get(export::export_logs))
+
+        // --- API REMINDER & NOTIFIKASI ---
+        .route("/api/reminders", get(reminders::list_reminders).post(reminders::create_reminder))
+        .route("/api/reminders/{id}", axum::routing::put(reminders::update_reminder).delete(reminders::delete_reminder))
+        .route("/api/reminders/{id}/snooze", post(reminders::snooze_reminder))
+        .route("/api/notifications", get(reminders::list_notifications))
+        .route("/api/notifications/{id}/read", post(reminders::mark_read))
+        .route("/api/notifications/read-all", post(reminders::mark_all_read))
+        .route("/api/logs/{id}/extract", post(actions::extract_now))
+        .route("/api/actions", get(actions::list_actions))
+        .route("/api/stats", get(stats::get_stats))
+        .route("/api/categories", get(categories::list_categories).post(categories::create_category))
+        .route("/api/categories/{id}", axum::routing::put(categories::update_category).delete(categories::delete_category))
+        .route("/api/categories/{id}/merge", post(categories::merge_category))
+        .route("/api/templates", get(templates::list_templates).post(templates::create_template))
+        .route("/api/templates/{id}", get(templates::get_template).put(templates::update_template).delete(templates::delete_template))
+        .route("/api/templates/{id}/apply", post(templates::apply_template))
+        .route("/api/goals", get(goals::list_goals).post(goals::create_goal))
+        .route("/api/goals/{id}", axum::routing::put(goals::update_goal).delete(goals::delete_goal))
+        .route("/api/goals/progress", get(goals::get_progress))
+        .route("/api/goals/schedule", get(goals::get_schedule).put(goals::update_schedule))
+        .route("/api/goals/holidays", get(goals::list_holidays).post(goals::create_holiday))
+        .route("/api/goals/holidays/{id}", axum::routing::delete(goals::delete_holiday))
+        .route("/api/actions/{id}/accept", post(actions::accept_action))
+        .route("/api/actions/{id}/dismiss", post(actions::dismiss_action))
+        .route("/api/import", post(import::import_logs).layer(DefaultBodyLimit::max(import::MAX_UPLOAD_BYTES)))
         
         // --- API PERSONAS ---
         .route("/api/personas", get(personas::get_personas))
@@ -183,14 +295,65 @@ let app = Router::new()
         .route("/api/ai/polish", post(ai_features::polish_content))
 
         .route("/api/settings", get(settings::get_settings).post(settings::update_settings))
-        
-        .layer(CorsLayer::permissive())
+
+        // --- API INTEGRATIONS (GitHub, dll) ---
+        .route("/api/integrations", get(integrations_api::list_integrations))
+        .route("/api/integrations/{service}", get(integrations_api::get_integration).put(integrations_api::update_integration))
+
+        // --- API WEBHOOKS (Notifikasi keluar) ---
+        .route("/api/webhooks", get(webhooks::list_targets).post(webhooks::create_target))
+        .route("/api/webhooks/{id}", axum::routing::put(webhooks::update_target).delete(webhooks::delete_target))
+        .route("/api/webhooks/deliveries", get(webhooks::list_deliveries))
+        .route("/api/webhooks/deliveries/{id}/replay", post(webhooks::replay_delivery))
+
+        // --- API AKUN & TOKEN ---
+        .route("/api/auth/password", post(auth::change_password))
+        .route("/api/auth/tokens", get(auth::list_tokens).post(auth::create_token))
+        .route("/api/auth/tokens/{id}", axum::routing::delete(auth::revoke_token))
+        .route("/api/admin/backup", post(backup::backup_now))
+        .route("/api/admin/backups", get(backup::list_backups))
+        .route("/api/admin/repair-tags", post(logbook::repair_tags_now))
+        .route("/api/users", get(users::list_users).post(users::create_user))
+
+        .route_layer(middleware::from_fn_with_state(pool.clone(), auth::require_auth));
+
+    let ui_dir = config.server.ui_dir.clone();
+    if ui::is_embedded() {
+        println!("🖥️ UI disajikan dari binary (embed-ui)");
+    } else {
+        println!("🖥️ UI disajikan dari {}", ui_dir.display());
+    }
+
+    let app = Router::new()
+        // --- API LOGIN (Public) ---
+        .route("/api/auth/status", get(auth::auth_status))
+        .route("/api/auth/setup", post(auth::setup))
+        .route("/api/auth/login", post(auth::login))
+        .route("/api/auth/logout", post(auth::logout))
+
+        .merge(protected)
+
+        // --- UI (file statis + SPA fallback) ---
+        .fallback(move |uri| ui::serve(ui_dir.clone(), uri))
+        .layer(cors)
         .with_state(pool);
 
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
-    println!("🚀 Server Logbook berjalan di http://localhost:3000");
-    axum::serve(listener, app).await?;
+    let addr = config.socket_addr();
+    match &config.server.tls {
+        Some(tls) => {
+            let rustls = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+                .await
+                .map_err(|e| format!("Gagal memuat sertifikat TLS: {}", e))?;
+            println!("🔐 Server Logbook berjalan di {}", config.public_url());
+            axum_server::bind_rustls(addr, rustls).serve(app.into_make_service()).await?;
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            println!("🚀 Server Logbook berjalan di {}", config.public_url());
+            axum::serve(listener, app).await?;
+        }
+    }
 
     Ok(())
 }
\ No newline at end of file
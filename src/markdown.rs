@@ -0,0 +1,42 @@
+use ammonia::Builder;
+use pulldown_cmark::{html, Options, Parser};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+// Markdown isi log -> HTML yang aman ditempel langsung ke halaman client lain.
+// Isi log bisa datang dari integrasi (GitHub, Telegram, import), jadi HTML mentah
+// di dalamnya selalu disaring oleh ammonia.
+
+static SANITIZER: OnceLock<Builder<'static>> = OnceLock::new();
+
+fn sanitizer() -> &'static Builder<'static> {
+    SANITIZER.get_or_init(|| {
+        let mut builder = Builder::default();
+        // Checkbox task list (- [x]) & kelas bahasa di blok kode (untuk syntax highlight di client)
+        builder
+            .add_tags(["input"])
+            .add_tag_attributes("input", ["type", "checked", "disabled"])
+            .add_tag_attributes("code", ["class"])
+            .attribute_filter(|tag, attr, value| match (tag, attr) {
+                ("input", "type") if value != "checkbox" => None,
+                ("code", "class") if !value.starts_with("language-") => None,
+                _ => Some(value.into()),
+            })
+            .url_schemes(HashSet::from(["http", "https", "mailto"]));
+        builder
+    })
+}
+
+pub fn render(content: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    // [[entry:123]] -> link Markdown ke API log tersebut
+    let source = crate::links::rewrite_links(content, |id| format!("[#{}](/api/logs/{})", id, id));
+    let mut unsafe_html = String::with_capacity(source.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, Parser::new_ext(&source, options));
+
+    sanitizer().clean(&unsafe_html).to_string()
+}
@@ -0,0 +1,285 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use serde_json::{json, Value};
+use reqwest::header;
+
+use crate::integrations::{ExternalItem, Integration, IntegrationConfig, PollResult};
+use crate::logbook::CreateLogRequest;
+
+const DEFAULT_BASE_URL: &str = "https://api.notion.com";
+const NOTION_VERSION: &str = "2022-06-28";
+
+// Rate limit Notion ~3 request/detik, jadi dicicil per siklus
+const BATCH_SIZE: usize = 20;
+// Batas Notion: 2000 karakter per rich_text, 100 block per request
+const TEXT_CHUNK: usize = 2000;
+const MAX_BLOCKS: usize = 100;
+
+// Penanda di external_refs.sync_fingerprint untuk page yang sudah diarsipkan
+const ARCHIVED: &str = "archived";
+
+// Sync satu arah log_entries -> database Notion.
+// Config: {"database_id": "...", "base_url": "..."}, integration token di kolom api_key.
+// Database Notion harus punya properti: Name (title), Date (date), Tags (multi-select),
+// Category (select), Mood (select), Source (select).
+pub struct NotionIntegration;
+
+#[derive(sqlx::FromRow)]
+struct SyncRow {
+    id: i64,
+    content: String,
+    entry_date: String,
+    entry_time: String,
+    tags: Option<String>,
+    category: Option<String>,
+    mood: Option<String>,
+    source: Option<String>,
+    is_deleted: bool,
+    page_id: Option<String>,
+    sync_fingerprint: Option<String>,
+}
+
+fn base_url(config: &Value) -> String {
+    config.get("base_url")
+        .and_then(Value::as_str)
+        .filter(|u| !u.is_empty())
+        .unwrap_or(DEFAULT_BASE_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn database_id(config: &Value) -> String {
+    config.get("database_id").and_then(Value::as_str).unwrap_or_default().to_string()
+}
+
+fn select(value: Option<&str>) -> Value {
+    match value.filter(|v| !v.is_empty()) {
+        // Notion menolak koma di nama opsi select
+        Some(v) => json!({ "select": { "name": v.replace(',', " ") } }),
+        None => json!({ "select": null }),
+    }
+}
+
+fn properties(row: &SyncRow) -> Value {
+    let tags = crate::logbook::parse_tags(row.tags.as_deref());
+    let title: String = row.content.lines().next().unwrap_or_default().chars().take(100).collect();
+
+    json!({
+        "Name": { "title": [{ "text": { "content": title } }] },
+        "Date": { "date": { "start": format!("{}T{}", row.entry_date, row.entry_time) } },
+        "Tags": { "multi_select": tags.iter().map(|t| json!({ "name": t.replace(',', " ") })).collect::<Vec<_>>() },
+        "Category": select(row.category.as_deref()),
+        "Mood": select(row.mood.as_deref()),
+        "Source": select(row.source.as_deref()),
+    })
+}
+
+// Isi catatan dipecah jadi paragraph block (maks 2000 karakter per block)
+fn content_blocks(content: &str) -> Vec<Value> {
+    let chars: Vec<char> = content.chars().collect();
+    chars.chunks(TEXT_CHUNK)
+        .take(MAX_BLOCKS)
+        .map(|chunk| json!({
+            "object": "block",
+            "type": "paragraph",
+            "paragraph": { "rich_text": [{ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }] }
+        }))
+        .collect()
+}
+
+// Sidik jari data yang terakhir dikirim, buat deteksi entri yang diedit
+fn fingerprint(row: &SyncRow) -> String {
+    json!([row.content, row.entry_date, row.entry_time, row.tags, row.category, row.mood, row.source]).to_string()
+}
+
+struct NotionClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl NotionClient {
+    fn new(config: &IntegrationConfig) -> Result<Self, String> {
+        let mut headers = header::HeaderMap::new();
+        headers.insert("Notion-Version", header::HeaderValue::from_static(NOTION_VERSION));
+        let auth = header::HeaderValue::from_str(&format!("Bearer {}", config.api_key))
+            .map_err(|_| "Token Notion berisi karakter tidak valid".to_string())?;
+        headers.insert(header::AUTHORIZATION, auth);
+
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(NotionClient { client, base_url: base_url(&config.config) })
+    }
+
+    async fn send(&self, method: reqwest::Method, path: &str, body: Option<Value>) -> Result<Value, String> {
+        let mut req = self.client.request(method, format!("{}/v1/{}", self.base_url, path));
+        if let Some(body) = body {
+            req = req.json(&body);
+        }
+
+        let res = req.send().await.map_err(|e| format!("Gagal koneksi HTTP: {}", e))?;
+        let status = res.status();
+        let data: Value = res.json().await.unwrap_or(Value::Null);
+
+        if !status.is_success() {
+            let message = data["message"].as_str().unwrap_or_default();
+            return Err(format!("Notion Error {}: {}", status, message));
+        }
+        Ok(data)
+    }
+
+    async fn create_page(&self, database_id: &str, row: &SyncRow) -> Result<String, String> {
+        let page = self.send(reqwest::Method::POST, "pages", Some(json!({
+            "parent": { "database_id": database_id },
+            "properties": properties(row),
+            "children": content_blocks(&row.content),
+        }))).await?;
+
+        page["id"].as_str()
+            .map(str::to_string)
+            .ok_or("Response Notion tidak berisi page id".to_string())
+    }
+
+    async fn update_page(&self, page_id: &str, row: &SyncRow) -> Result<(), String> {
+        self.send(reqwest::Method::PATCH, &format!("pages/{}", page_id), Some(json!({
+            "properties": properties(row),
+        }))).await?;
+
+        // Isi page diganti total: hapus block lama, tulis ulang
+        let children = self.send(reqwest::Method::GET, &format!("blocks/{}/children?page_size=100", page_id), None).await?;
+        for block in children["results"].as_array().into_iter().flatten() {
+            if let Some(block_id) = block["id"].as_str() {
+                self.send(reqwest::Method::DELETE, &format!("blocks/{}", block_id), None).await?;
+            }
+        }
+        self.send(reqwest::Method::PATCH, &format!("blocks/{}/children", page_id), Some(json!({
+            "children": content_blocks(&row.content),
+        }))).await?;
+
+        Ok(())
+    }
+
+    async fn archive_page(&self, page_id: &str) -> Result<(), String> {
+        self.send(reqwest::Method::PATCH, &format!("pages/{}", page_id), Some(json!({ "archived": true }))).await?;
+        Ok(())
+    }
+}
+
+async fn save_ref(pool: &SqlitePool, user_id: i64, page_id: &str, log_id: i64, fingerprint: &str) -> Result<(), String> {
+    sqlx::query(
+        "INSERT INTO external_refs (user_id, service_name, external_id, log_entry_id, sync_fingerprint) VALUES (?, 'notion', ?, ?, ?)
+         ON CONFLICT(user_id, service_name, external_id) DO UPDATE SET sync_fingerprint = excluded.sync_fingerprint"
+    )
+    .bind(user_id)
+    .bind(page_id)
+    .bind(log_id)
+    .bind(fingerprint)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Kirim satu entri, balikin true kalau ada perubahan yang dikirim ke Notion
+async fn sync_row(pool: &SqlitePool, user_id: i64, notion: &NotionClient, database_id: &str, row: &SyncRow) -> Result<bool, String> {
+    let current = fingerprint(row);
+
+    match (&row.page_id, row.is_deleted) {
+        // Entri baru
+        (None, false) => {
+            let page_id = notion.create_page(database_id, row).await?;
+            save_ref(pool, user_id, &page_id, row.id, &current).await?;
+        }
+        // Entri dihapus (masuk trash) -> arsipkan page-nya
+        (Some(page_id), true) if row.sync_fingerprint.as_deref() != Some(ARCHIVED) => {
+            notion.archive_page(page_id).await?;
+            save_ref(pool, user_id, page_id, row.id, ARCHIVED).await?;
+        }
+        // Entri diedit sejak sync terakhir
+        (Some(page_id), false) if row.sync_fingerprint.as_deref() != Some(current.as_str()) => {
+            notion.update_page(page_id, row).await?;
+            save_ref(pool, user_id, page_id, row.id, &current).await?;
+        }
+        _ => return Ok(false),
+    }
+
+    Ok(true)
+}
+
+#[async_trait]
+impl Integration for NotionIntegration {
+    fn service_name(&self) -> &'static str {
+        "notion"
+    }
+
+    fn configure(&self, config: Value) -> Result<Value, String> {
+        // Database ID boleh ditempel dalam bentuk URL Notion: ambil 32 karakter hex di akhir path
+        let raw = database_id(&config).trim().to_string();
+        let tail = raw.split('?').next().unwrap_or_default().rsplit(['/', '-']).next().unwrap_or_default();
+        let id = if tail.len() == 32 && tail.chars().all(|c| c.is_ascii_hexdigit()) { tail } else { raw.as_str() };
+
+        Ok(json!({ "database_id": id, "base_url": base_url(&config) }))
+    }
+
+    fn validate(&self, config: &IntegrationConfig) -> Result<(), String> {
+        if config.api_key.trim().is_empty() {
+            return Err("Integration token Notion (api_key) belum diisi".to_string());
+        }
+        if database_id(&config.config).is_empty() {
+            return Err("database_id belum diisi".to_string());
+        }
+        Ok(())
+    }
+
+    // Integrasi keluar: tugasnya mendorong data, gak ada item yang diimpor
+    async fn poll(&self, pool: &SqlitePool, config: &IntegrationConfig) -> Result<PollResult, String> {
+        let notion = NotionClient::new(config)?;
+        let database_id = database_id(&config.config);
+
+        // Entri milik user ini yang belum pernah dikirim + semua yang sudah punya page (dicek perubahannya)
+        let rows: Vec<SyncRow> = sqlx::query_as(
+            "SELECT l.id, l.content, l.entry_date, l.entry_time, l.tags, l.category, l.mood, l.source,
+                    COALESCE(l.is_deleted, FALSE) as is_deleted, r.external_id as page_id, r.sync_fingerprint
+             FROM log_entries l
+             LEFT JOIN external_refs r ON r.log_entry_id = l.id AND r.service_name = 'notion' AND r.user_id = l.user_id
+             WHERE l.user_id = ? AND (r.id IS NOT NULL OR l.is_deleted = FALSE)
+             ORDER BY l.entry_date ASC, l.entry_time ASC"
+        )
+        .bind(config.user_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut pushed = 0;
+        let mut errors = Vec::new();
+
+        for row in &rows {
+            if pushed >= BATCH_SIZE {
+                break;
+            }
+            match sync_row(pool, config.user_id, &notion, &database_id, row).await {
+                Ok(true) => pushed += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    pushed += 1;
+                    errors.push(format!("entri #{}: {}", row.id, e));
+                }
+            }
+        }
+
+        if pushed > 0 {
+            println!("📤 [notion] {} entri dikirim ke Notion.", pushed - errors.len());
+        }
+        if let Some(first) = errors.first() {
+            return Err(format!("{} entri gagal dikirim, contoh {}", errors.len(), first));
+        }
+
+        Ok(PollResult::default())
+    }
+
+    fn to_log_entry(&self, _item: &ExternalItem) -> Option<CreateLogRequest> {
+        None
+    }
+}
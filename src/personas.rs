@@ -1,10 +1,35 @@
 use axum::{
     extract::{State, Path},
     response::Json,
-    http::StatusCode,
+    Extension,
 };
 use sqlx::SqlitePool;
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult};
+
+// 3 Persona Dasar: (nama, deskripsi, system prompt). Yang pertama aktif secara default.
+const DEFAULT_PERSONAS: &[(&str, &str, &str)] = &[
+    // 1. The Scribe (Pencatat)
+    (
+        "The Scribe",
+        "Pencatat logbook yang rapi dan formal.",
+        "Kamu adalah Scribe. Tugasmu adalah merapikan input pengguna menjadi entri logbook yang terstruktur, jelas, dan kronologis. Jangan banyak bicara, fokus pada akurasi pencatatan.",
+    ),
+    // 2. Discussion Partner (Teman Diskusi)
+    (
+        "Discussion Partner",
+        "Teman brainstorming dan curhat.",
+        "Kamu adalah Partner diskusi. Dengarkan ide, keluh kesah, atau rencana pengguna. Berikan feedback konstruktif, pertanyaan pancingan, dan dukungan emosional. Jadilah teman yang cerdas.",
+    ),
+    // 3. The Helper (Asisten)
+    (
+        "The Helper",
+        "Asisten manajemen waktu dan pengingat.",
+        "Kamu adalah Helper. Tugasmu mengingatkan jadwal, mencari data di log lama, dan memastikan tidak ada tugas yang terlewat. Kamu sangat teliti terhadap waktu dan deadline.",
+    ),
+];
 
 #[derive(Serialize, sqlx::FromRow)]
 pub struct Persona {
@@ -15,16 +40,46 @@ pub struct Persona {
     pub is_custom: bool,
 }
 
+const PERSONA_COLUMNS: &str = "id, name, description, is_active, is_custom";
+
+// Seed persona dasar untuk user yang belum punya persona sama sekali
+pub async fn seed_defaults(pool: &SqlitePool, user_id: i64) -> Result<(), sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ai_personas WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+    if count > 0 {
+        return Ok(());
+    }
+
+    for (i, (name, description, prompt)) in DEFAULT_PERSONAS.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO ai_personas (user_id, name, description, system_prompt, is_active) VALUES (?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(description)
+        .bind(prompt)
+        .bind(i == 0) // Default active: Scribe
+        .execute(pool)
+        .await?;
+    }
+
+    println!("🌱 Persona AI Dasar (Scribe, Partner, Helper) telah ditanam untuk user #{}.", user_id);
+    Ok(())
+}
+
 // 1. GET ALL PERSONAS
 pub async fn get_personas(
     State(pool): State<SqlitePool>,
-) -> Result<Json<Vec<Persona>>, StatusCode> {
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<Persona>>> {
     let personas = sqlx::query_as::<_, Persona>(
-        "SELECT id, name, description, is_active, is_custom FROM ai_personas ORDER BY id ASC"
+        &format!("SELECT {} FROM ai_personas WHERE user_id = ? ORDER BY id ASC", PERSONA_COLUMNS)
     )
+    .bind(user.id)
     .fetch_all(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    .await?;
 
     Ok(Json(personas))
 }
@@ -32,14 +87,28 @@ pub async fn get_personas(
 // 2. ACTIVATE PERSONA (Switch Personality)
 pub async fn activate_persona(
     State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
     Path(id): Path<i64>,
-) -> Result<Json<String>, StatusCode> {
-    
-    // Matikan semua dulu
-    sqlx::query("UPDATE ai_personas SET is_active = FALSE").execute(&pool).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+) -> AppResult<Json<Persona>> {
     
-    // Hidupkan yang dipilih
-    sqlx::query("UPDATE ai_personas SET is_active = TRUE WHERE id = ?").bind(id).execute(&pool).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    // Persona user lain gak boleh disentuh
+    let owned: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM ai_personas WHERE id = ? AND user_id = ?)")
+        .bind(id).bind(user.id)
+        .fetch_one(&pool).await?;
+    if !owned {
+        return Err(AppError::not_found(format!("Persona #{} tidak ditemukan", id)));
+    }
+
+    // Matikan semua lalu hidupkan yang dipilih, dalam satu transaksi
+    let mut tx = pool.begin().await?;
+    sqlx::query("UPDATE ai_personas SET is_active = FALSE WHERE user_id = ?").bind(user.id).execute(&mut *tx).await?;
+    sqlx::query("UPDATE ai_personas SET is_active = TRUE WHERE id = ?").bind(id).execute(&mut *tx).await?;
+    tx.commit().await?;
+
+    let persona = sqlx::query_as::<_, Persona>(&format!("SELECT {} FROM ai_personas WHERE id = ?", PERSONA_COLUMNS))
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
 
-    Ok(Json(format!("Persona ID {} diaktifkan", id)))
+    Ok(Json(persona))
 }
\ No newline at end of file
@@ -0,0 +1,525 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use chrono::{Duration, Months, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::SqlitePool;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError, JsonBody};
+
+// Waktu reminder disimpan sebagai waktu lokal server ("YYYY-MM-DD HH:MM:SS"),
+// sama seperti entry_date/entry_time di log_entries
+const DATETIME: &str = "%Y-%m-%d %H:%M:%S";
+
+const RECURRENCES: &[&str] = &["none", "daily", "weekly", "monthly", "yearly"];
+const STATUSES: &[&str] = &["pending", "fired", "done", "cancelled"];
+
+const CHECK_EVERY: std::time::Duration = std::time::Duration::from_secs(30);
+const TITLE_MAX: usize = 200;
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS reminders (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            log_entry_id INTEGER REFERENCES log_entries(id) ON DELETE SET NULL, -- Opsional
+            title TEXT NOT NULL,
+            note TEXT,
+
+            due_at TEXT NOT NULL,              -- Jadwal berikutnya (waktu lokal)
+            snoozed_until TEXT,                -- Kalau diisi, ini yang dipakai scheduler
+            recurrence TEXT DEFAULT 'none',    -- none, daily, weekly, monthly, yearly
+            recurrence_interval INTEGER DEFAULT 1, -- Tiap N hari/minggu/bulan/tahun
+            recurrence_until TEXT,             -- YYYY-MM-DD, batas terakhir pengulangan
+
+            status TEXT DEFAULT 'pending',     -- pending, fired, done, cancelled
+            last_fired_at TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );"
+    ).execute(pool).await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_reminders_due ON reminders (status, due_at)")
+        .execute(pool)
+        .await?;
+
+    // Inbox notifikasi di dalam aplikasi
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS notifications (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            reminder_id INTEGER REFERENCES reminders(id) ON DELETE SET NULL,
+            title TEXT NOT NULL,
+            body TEXT,
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            read_at DATETIME
+        );"
+    ).execute(pool).await?;
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_notifications_user ON notifications (user_id, read_at)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+// ==========================================
+// MODEL
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Reminder {
+    pub id: i64,
+    pub log_entry_id: Option<i64>,
+    pub title: String,
+    pub note: Option<String>,
+    pub due_at: String,
+    pub snoozed_until: Option<String>,
+    pub recurrence: String,
+    pub recurrence_interval: i64,
+    pub recurrence_until: Option<String>,
+    pub status: String,
+    pub last_fired_at: Option<String>,
+    pub created_at: String,
+}
+
+const REMINDER_COLUMNS: &str = "id, log_entry_id, title, note, due_at, snoozed_until,
+    COALESCE(recurrence, 'none') as recurrence, COALESCE(recurrence_interval, 1) as recurrence_interval, recurrence_until,
+    COALESCE(status, 'pending') as status, last_fired_at, CAST(created_at AS TEXT) as created_at";
+
+#[derive(Deserialize)]
+pub struct ReminderRequest {
+    pub title: String,
+    pub note: Option<String>,
+    pub due_at: String,                   // "2024-05-01 09:00" atau "2024-05-01T09:00"
+    pub log_entry_id: Option<i64>,
+    pub recurrence: Option<String>,
+    pub recurrence_interval: Option<i64>,
+    pub recurrence_until: Option<String>, // YYYY-MM-DD
+    pub status: Option<String>,           // Hanya dipakai saat update (misal tandai "done")
+}
+
+#[derive(Deserialize)]
+pub struct ReminderFilter {
+    pub status: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SnoozeRequest {
+    pub minutes: Option<i64>,  // Tunda N menit dari sekarang
+    pub until: Option<String>, // Atau sampai waktu tertentu
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Notification {
+    pub id: i64,
+    pub reminder_id: Option<i64>,
+    pub title: String,
+    pub body: Option<String>,
+    pub created_at: String,
+    pub read_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct NotificationFilter {
+    pub unread: Option<bool>,
+}
+
+// Input dari form HTML (datetime-local) atau API, detik boleh tidak ada
+pub fn parse_datetime(raw: &str) -> Option<NaiveDateTime> {
+    let raw = raw.trim();
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(raw, fmt).ok())
+}
+
+fn now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+// Jadwal berikutnya sesudah `from`. Tanggal 31 di bulan pendek otomatis jadi tanggal terakhir bulan itu.
+fn next_occurrence(from: NaiveDateTime, recurrence: &str, interval: i64) -> Option<NaiveDateTime> {
+    let interval = interval.max(1);
+    match recurrence {
+        "daily" => from.checked_add_signed(Duration::days(interval)),
+        "weekly" => from.checked_add_signed(Duration::weeks(interval)),
+        "monthly" => from.checked_add_months(Months::new(interval as u32)),
+        "yearly" => from.checked_add_months(Months::new(interval as u32 * 12)),
+        _ => None,
+    }
+}
+
+struct ValidReminder {
+    title: String,
+    note: Option<String>,
+    due_at: NaiveDateTime,
+    recurrence: String,
+    interval: i64,
+    until: Option<NaiveDate>,
+    status: Option<String>,
+}
+
+async fn validate(pool: &SqlitePool, user_id: i64, payload: ReminderRequest) -> AppResult<(ValidReminder, Option<i64>)> {
+    let mut errors = Vec::new();
+
+    let title = payload.title.trim().to_string();
+    if title.is_empty() {
+        errors.push(FieldError::new("title", "Judul reminder wajib diisi"));
+    } else if title.chars().count() > TITLE_MAX {
+        errors.push(FieldError::new("title", format!("Maksimal {} karakter", TITLE_MAX)));
+    }
+
+    let due_at = parse_datetime(&payload.due_at);
+    if due_at.is_none() {
+        errors.push(FieldError::new("due_at", format!("Format waktu harus YYYY-MM-DD HH:MM, dapat '{}'", payload.due_at)));
+    }
+
+    let recurrence = payload.recurrence.unwrap_or_else(|| "none".to_string()).trim().to_lowercase();
+    if !RECURRENCES.contains(&recurrence.as_str()) {
+        errors.push(FieldError::new("recurrence", format!("Pilih salah satu: {}", RECURRENCES.join(", "))));
+    }
+
+    let interval = payload.recurrence_interval.unwrap_or(1);
+    if !(1..=365).contains(&interval) {
+        errors.push(FieldError::new("recurrence_interval", "Harus 1-365"));
+    }
+
+    let until = match payload.recurrence_until.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        None => None,
+        Some(raw) => match NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                errors.push(FieldError::new("recurrence_until", format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", raw)));
+                None
+            }
+        },
+    };
+
+    let status = payload.status.map(|s| s.trim().to_lowercase());
+    if let Some(status) = &status {
+        if !STATUSES.contains(&status.as_str()) {
+            errors.push(FieldError::new("status", format!("Pilih salah satu: {}", STATUSES.join(", "))));
+        }
+    }
+
+    // Entry yang ditautkan harus milik user sendiri
+    if let Some(log_id) = payload.log_entry_id {
+        if crate::logbook::fetch_log(pool, user_id, log_id).await?.is_none() {
+            errors.push(FieldError::new("log_entry_id", format!("Log #{} tidak ditemukan", log_id)));
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+
+    let note = payload.note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+    let reminder = ValidReminder {
+        title,
+        note,
+        due_at: due_at.unwrap_or_default(),
+        recurrence,
+        interval,
+        until,
+        status,
+    };
+    Ok((reminder, payload.log_entry_id))
+}
+
+async fn find(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<Reminder> {
+    sqlx::query_as::<_, Reminder>(&format!("SELECT {} FROM reminders WHERE id = ? AND user_id = ?", REMINDER_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::not_found(format!("Reminder #{} tidak ditemukan", id)))
+}
+
+// ==========================================
+// API REMINDER
+// ==========================================
+
+// GET /api/reminders?status=pending
+pub async fn list_reminders(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(filter): Query<ReminderFilter>,
+) -> AppResult<Json<Vec<Reminder>>> {
+    let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(format!("SELECT {} FROM reminders WHERE user_id = ", REMINDER_COLUMNS));
+    qb.push_bind(user.id);
+    if let Some(status) = filter.status {
+        qb.push(" AND status = ").push_bind(status);
+    }
+    qb.push(" ORDER BY COALESCE(snoozed_until, due_at) ASC");
+
+    Ok(Json(qb.build_query_as::<Reminder>().fetch_all(&pool).await?))
+}
+
+// POST /api/reminders
+pub async fn create_reminder(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<ReminderRequest>,
+) -> AppResult<(StatusCode, Json<Reminder>)> {
+    let reminder = insert_reminder(&pool, user.id, payload).await?;
+    Ok((StatusCode::CREATED, Json(reminder)))
+}
+
+// Dipakai handler & fitur lain (misal usulan tugas dari AI) supaya validasinya sama
+pub async fn insert_reminder(pool: &SqlitePool, user_id: i64, payload: ReminderRequest) -> AppResult<Reminder> {
+    let (r, log_entry_id) = validate(pool, user_id, payload).await?;
+
+    let id = sqlx::query(
+        "INSERT INTO reminders (user_id, log_entry_id, title, note, due_at, recurrence, recurrence_interval, recurrence_until)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
+    )
+    .bind(user_id)
+    .bind(log_entry_id)
+    .bind(&r.title)
+    .bind(&r.note)
+    .bind(r.due_at.format(DATETIME).to_string())
+    .bind(&r.recurrence)
+    .bind(r.interval)
+    .bind(r.until.map(|d| d.format("%Y-%m-%d").to_string()))
+    .execute(pool)
+    .await?
+    .last_insert_rowid();
+
+    find(pool, user_id, id).await
+}
+
+// PUT /api/reminders/{id} (jadwal baru = snooze direset & status kembali pending)
+pub async fn update_reminder(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<ReminderRequest>,
+) -> AppResult<Json<Reminder>> {
+    let existing = find(&pool, user.id, id).await?;
+    let (r, log_entry_id) = validate(&pool, user.id, payload).await?;
+
+    let due_at = r.due_at.format(DATETIME).to_string();
+    let rescheduled = due_at != existing.due_at;
+    let status = r.status.unwrap_or_else(|| if rescheduled { "pending".to_string() } else { existing.status.clone() });
+
+    sqlx::query(
+        "UPDATE reminders SET log_entry_id = ?, title = ?, note = ?, due_at = ?, recurrence = ?, recurrence_interval = ?,
+                recurrence_until = ?, status = ?, snoozed_until = CASE WHEN ? THEN NULL ELSE snoozed_until END
+         WHERE id = ? AND user_id = ?"
+    )
+    .bind(log_entry_id)
+    .bind(&r.title)
+    .bind(&r.note)
+    .bind(&due_at)
+    .bind(&r.recurrence)
+    .bind(r.interval)
+    .bind(r.until.map(|d| d.format("%Y-%m-%d").to_string()))
+    .bind(&status)
+    .bind(rescheduled)
+    .bind(id)
+    .bind(user.id)
+    .execute(&pool)
+    .await?;
+
+    Ok(Json(find(&pool, user.id, id).await?))
+}
+
+// DELETE /api/reminders/{id}
+pub async fn delete_reminder(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<String>> {
+    let result = sqlx::query("DELETE FROM reminders WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Reminder #{} tidak ditemukan", id)));
+    }
+    Ok(Json("Reminder dihapus".to_string()))
+}
+
+// POST /api/reminders/{id}/snooze {"minutes": 15} atau {"until": "2024-05-01 09:00"}
+pub async fn snooze_reminder(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<SnoozeRequest>,
+) -> AppResult<Json<Reminder>> {
+    let existing = find(&pool, user.id, id).await?;
+    if matches!(existing.status.as_str(), "done" | "cancelled") {
+        return Err(AppError::conflict(format!("Reminder #{} sudah {}", id, existing.status)));
+    }
+
+    let until = match (payload.minutes, payload.until.as_deref()) {
+        (Some(minutes), None) if (1..=60 * 24 * 30).contains(&minutes) => now() + Duration::minutes(minutes),
+        (None, Some(raw)) => match parse_datetime(raw) {
+            Some(at) if at > now() => at,
+            Some(_) => return Err(AppError::validation(vec![FieldError::new("until", "Waktu snooze harus di masa depan")])),
+            None => return Err(AppError::validation(vec![FieldError::new("until", format!("Format waktu harus YYYY-MM-DD HH:MM, dapat '{}'", raw))])),
+        },
+        _ => return Err(AppError::validation(vec![FieldError::new("minutes", "Isi salah satu: minutes (1-43200) atau until")])),
+    };
+
+    sqlx::query("UPDATE reminders SET snoozed_until = ?, status = 'pending' WHERE id = ? AND user_id = ?")
+        .bind(until.format(DATETIME).to_string())
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+
+    Ok(Json(find(&pool, user.id, id).await?))
+}
+
+// ==========================================
+// API INBOX NOTIFIKASI
+// ==========================================
+
+// GET /api/notifications?unread=true
+pub async fn list_notifications(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(filter): Query<NotificationFilter>,
+) -> AppResult<Json<Vec<Notification>>> {
+    let unread_only = filter.unread.unwrap_or(false);
+    let notifications = sqlx::query_as::<_, Notification>(
+        "SELECT id, reminder_id, title, body, CAST(created_at AS TEXT) as created_at, CAST(read_at AS TEXT) as read_at
+         FROM notifications WHERE user_id = ? AND (? = FALSE OR read_at IS NULL)
+         ORDER BY id DESC LIMIT 200"
+    )
+    .bind(user.id)
+    .bind(unread_only)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(notifications))
+}
+
+// POST /api/notifications/{id}/read
+pub async fn mark_read(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<String>> {
+    let result = sqlx::query("UPDATE notifications SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP) WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Notifikasi #{} tidak ditemukan", id)));
+    }
+    Ok(Json("Notifikasi ditandai sudah dibaca".to_string()))
+}
+
+// POST /api/notifications/read-all
+pub async fn mark_all_read(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<String>> {
+    let updated = sqlx::query("UPDATE notifications SET read_at = CURRENT_TIMESTAMP WHERE user_id = ? AND read_at IS NULL")
+        .bind(user.id)
+        .execute(&pool)
+        .await?
+        .rows_affected();
+    Ok(Json(format!("{} notifikasi ditandai sudah dibaca", updated)))
+}
+
+// Notifikasi inbox di luar reminder (misal usulan tugas baru dari AI)
+pub async fn push_notification(pool: &SqlitePool, user_id: i64, title: &str, body: Option<&str>) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO notifications (user_id, title, body) VALUES (?, ?, ?)")
+        .bind(user_id)
+        .bind(title)
+        .bind(body)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ==========================================
+// SCHEDULER
+// ==========================================
+
+#[derive(sqlx::FromRow)]
+struct DueReminder {
+    id: i64,
+    user_id: i64,
+    log_entry_id: Option<i64>,
+    title: String,
+    note: Option<String>,
+    due_at: String,
+    recurrence: String,
+    recurrence_interval: i64,
+    recurrence_until: Option<String>,
+}
+
+// Kirim reminder yang sudah jatuh tempo ke inbox + webhook, lalu jadwalkan ulang kalau berulang
+pub async fn start_scheduler(pool: SqlitePool) {
+    loop {
+        if let Err(e) = fire_due(&pool).await {
+            eprintln!("❌ Scheduler reminder gagal: {}", e);
+        }
+        tokio::time::sleep(CHECK_EVERY).await;
+    }
+}
+
+async fn fire_due(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let now = now();
+    let now_text = now.format(DATETIME).to_string();
+
+    let due: Vec<DueReminder> = sqlx::query_as(
+        "SELECT id, user_id, log_entry_id, title, note, due_at, COALESCE(recurrence, 'none') as recurrence,
+                COALESCE(recurrence_interval, 1) as recurrence_interval, recurrence_until
+         FROM reminders WHERE status = 'pending' AND COALESCE(snoozed_until, due_at) <= ?"
+    )
+    .bind(&now_text)
+    .fetch_all(pool)
+    .await?;
+
+    for reminder in due {
+        // Jadwal berikutnya dihitung dari jadwal asli (bukan dari snooze), jadwal yang terlewat dilompati
+        let until = reminder.recurrence_until.as_deref().and_then(|u| NaiveDate::parse_from_str(u, "%Y-%m-%d").ok());
+        let mut next = parse_datetime(&reminder.due_at);
+        while let Some(at) = next.filter(|at| *at <= now) {
+            next = next_occurrence(at, &reminder.recurrence, reminder.recurrence_interval);
+        }
+        let next = next.filter(|at| until.is_none_or(|u| at.date() <= u));
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("INSERT INTO notifications (user_id, reminder_id, title, body) VALUES (?, ?, ?, ?)")
+            .bind(reminder.user_id)
+            .bind(reminder.id)
+            .bind(format!("⏰ {}", reminder.title))
+            .bind(&reminder.note)
+            .execute(&mut *tx)
+            .await?;
+
+        match next {
+            Some(at) => sqlx::query("UPDATE reminders SET due_at = ?, snoozed_until = NULL, last_fired_at = ? WHERE id = ?")
+                .bind(at.format(DATETIME).to_string()),
+            None => sqlx::query("UPDATE reminders SET status = 'fired', snoozed_until = NULL, last_fired_at = ? WHERE id = ?"),
+        }
+        .bind(&now_text)
+        .bind(reminder.id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        crate::webhooks::notify(pool, reminder.user_id, "reminder_due", json!({
+            "id": reminder.id,
+            "title": reminder.title,
+            "note": reminder.note,
+            "due_at": reminder.due_at,
+            "log_entry_id": reminder.log_entry_id,
+            "next_due_at": next.map(|at| at.format(DATETIME).to_string()),
+        })).await;
+    }
+
+    Ok(())
+}
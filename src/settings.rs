@@ -1,11 +1,14 @@
 use axum::{
     extract::State,
     response::Json,
-    Json as JsonBody,
+    Extension,
 };
 use sqlx::SqlitePool;
 use serde::{Deserialize, Serialize};
 
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, JsonBody};
+
 #[derive(Serialize)]
 pub struct AppSettings {
     pub username: String,
@@ -14,6 +17,7 @@ pub struct AppSettings {
     pub ai_model_name: String,
     pub is_api_key_set: bool, // Kita cuma kasih tau "udah diset" atau "belum", jangan kirim key aslinya
     pub use_local_ai: bool,
+    pub auto_extract_actions: bool, // Usulkan tugas/reminder dari log baru
 }
 
 #[derive(Deserialize)]
@@ -21,65 +25,112 @@ pub struct UpdateSettingsRequest {
     pub username: String,
     pub ai_api_key: String, // Bisa kosong kalau gak mau update
     pub ai_model_name: String,
+    pub auto_extract_actions: Option<bool>, // Tidak dikirim = tidak diubah
 }
 
-// GET SETTINGS
-pub async fn get_settings(
-    State(pool): State<SqlitePool>,
-) -> Json<AppSettings> {
-    let row = sqlx::query!(
-        "SELECT username, theme, ai_provider, ai_model_name, ai_api_key, use_local_ai FROM app_settings LIMIT 1"
+#[derive(sqlx::FromRow)]
+struct SettingsRow {
+    username: Option<String>,
+    theme: Option<String>,
+    ai_provider: Option<String>,
+    ai_model_name: Option<String>,
+    ai_api_key: Option<String>,
+    use_local_ai: Option<bool>,
+    auto_extract_actions: Option<bool>,
+}
+
+// Satu baris app_settings per user
+pub async fn seed_defaults(pool: &SqlitePool, user_id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO app_settings (username, user_id)
+         SELECT username, id FROM users WHERE id = ? AND NOT EXISTS (SELECT 1 FROM app_settings WHERE user_id = ?)"
     )
-    .fetch_optional(&pool)
-    .await
-    .unwrap_or(None);
+    .bind(user_id)
+    .bind(user_id)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// Settings versi aman (tanpa API key) untuk dikirim ke UI
+async fn load_settings(pool: &SqlitePool, user: AuthUser) -> Result<AppSettings, sqlx::Error> {
+    let row = sqlx::query_as::<_, SettingsRow>(
+        "SELECT username, theme, ai_provider, ai_model_name, ai_api_key, use_local_ai, auto_extract_actions FROM app_settings WHERE user_id = ? LIMIT 1"
+    )
+    .bind(user.id)
+    .fetch_optional(pool)
+    .await?;
 
-    if let Some(r) = row {
-        Json(AppSettings {
+    let settings = if let Some(r) = row {
+        AppSettings {
             username: r.username.unwrap_or("User".to_string()),
             theme: r.theme.unwrap_or("dark".to_string()),
             ai_provider: r.ai_provider.unwrap_or("gemini".to_string()),
             ai_model_name: r.ai_model_name.unwrap_or("gemini-1.5-flash".to_string()),
-            is_api_key_set: r.ai_api_key.is_some() && !r.ai_api_key.unwrap().is_empty(),
+            is_api_key_set: crate::crypto::reveal(r.ai_api_key).is_some(),
             use_local_ai: r.use_local_ai.unwrap_or(false),
-        })
+            auto_extract_actions: r.auto_extract_actions.unwrap_or(false),
+        }
     } else {
         // Default fallback updated to 2026 standard
-        Json(AppSettings {
-            username: "User".to_string(),
+        AppSettings {
+            username: user.username,
             theme: "dark".to_string(),
             ai_provider: "gemini".to_string(),
             ai_model_name: "gemini-3-flash-preview".to_string(), // <--- Default Baru
             is_api_key_set: false,
             use_local_ai: false,
-        })
-    }
+            auto_extract_actions: false,
+        }
+    };
+
+    Ok(settings)
+}
+
+// GET SETTINGS
+pub async fn get_settings(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<AppSettings>> {
+    Ok(Json(load_settings(&pool, user).await?))
 }
 
 // UPDATE SETTINGS
 pub async fn update_settings(
     State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
     JsonBody(payload): JsonBody<UpdateSettingsRequest>,
-) -> Json<String> {
+) -> AppResult<Json<AppSettings>> {
     
     // 1. Update Username & Model
-    let _ = sqlx::query!(
-        "UPDATE app_settings SET username = ?, ai_model_name = ? WHERE id = (SELECT id FROM app_settings LIMIT 1)",
-        payload.username,
-        payload.ai_model_name
-    )
-    .execute(&pool)
-    .await;
+    let updated = sqlx::query("UPDATE app_settings SET username = ?, ai_model_name = ? WHERE user_id = ?")
+        .bind(&payload.username)
+        .bind(&payload.ai_model_name)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    if updated.rows_affected() == 0 {
+        return Err(AppError::not_found("Settings belum ada untuk user ini"));
+    }
+
+    if let Some(auto_extract) = payload.auto_extract_actions {
+        sqlx::query("UPDATE app_settings SET auto_extract_actions = ? WHERE user_id = ?")
+            .bind(auto_extract)
+            .bind(user.id)
+            .execute(&pool)
+            .await?;
+    }
 
     // 2. Update API Key (Hanya jika diisi user)
     if !payload.ai_api_key.trim().is_empty() {
-        let _ = sqlx::query!(
-            "UPDATE app_settings SET ai_api_key = ? WHERE id = (SELECT id FROM app_settings LIMIT 1)",
-            payload.ai_api_key
-        )
-        .execute(&pool)
-        .await;
+        // Disimpan terenkripsi, lihat crypto.rs
+        let encrypted_key = crate::crypto::encrypt(payload.ai_api_key.trim());
+        sqlx::query("UPDATE app_settings SET ai_api_key = ? WHERE user_id = ?")
+            .bind(encrypted_key)
+            .bind(user.id)
+            .execute(&pool)
+            .await?;
     }
 
-    Json("Settings updated".to_string())
-}
\ No newline at end of file
+    Ok(Json(load_settings(&pool, user).await?))
+}
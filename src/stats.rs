@@ -0,0 +1,377 @@
+use axum::{
+    extract::{Query, State},
+    response::Json,
+    Extension,
+};
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{QueryBuilder, Sqlite, SqlitePool};
+use std::collections::BTreeMap;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError};
+
+// Tanpa from/to: 30 hari terakhir
+const DEFAULT_DAYS: i64 = 30;
+// per_day diisi lengkap (termasuk hari kosong), jadi rentangnya dibatasi
+const MAX_DAYS: i64 = 3660;
+const BUCKETS: &[&str] = &["day", "week", "month"];
+
+// GET /api/stats?from=2024-01-01&to=2024-03-31&bucket=week&top=10
+#[derive(Deserialize)]
+pub struct StatsQuery {
+    pub from: Option<String>,   // YYYY-MM-DD, inklusif
+    pub to: Option<String>,     // YYYY-MM-DD, inklusif (default hari ini)
+    pub bucket: Option<String>, // Periode grafik mood: day, week (default), month
+    pub top: Option<i64>,       // Jumlah tag/kategori teratas (default 10)
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub range: StatsRange,
+    pub totals: Totals,
+    pub per_day: Vec<PeriodCount>,
+    pub per_week: Vec<PeriodCount>,
+    pub streaks: Streaks,
+    pub moods: MoodStats,
+    pub top_tags: Vec<NameCount>,
+    pub categories: Vec<NameCount>,
+    pub sources: Vec<NameCount>,
+}
+
+#[derive(Serialize)]
+pub struct StatsRange {
+    pub from: String,
+    pub to: String,
+    pub days: i64,
+    pub bucket: String,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Totals {
+    pub entries: i64,
+    pub active_days: i64,
+    pub avg_length: f64, // Rata-rata panjang isi log (karakter)
+    pub total_length: i64,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct PeriodCount {
+    pub period: String, // Tanggal, atau hari Senin awal minggu untuk per_week
+    pub count: i64,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct NameCount {
+    pub name: String,
+    pub count: i64,
+}
+
+// Streak dihitung dari seluruh riwayat, bukan hanya rentang yang diminta
+#[derive(Serialize, Default)]
+pub struct Streaks {
+    pub current: i64,              // Hari berturut-turut sampai hari ini (atau kemarin kalau hari ini belum nulis)
+    pub longest: i64,
+    pub longest_start: Option<String>,
+    pub longest_end: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MoodStats {
+    pub distribution: Vec<MoodCount>,
+    pub over_time: Vec<MoodPeriod>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct MoodCount {
+    pub mood: Option<String>, // null = log tanpa mood
+    pub count: i64,
+    #[sqlx(skip)]
+    pub percent: f64,
+}
+
+#[derive(Serialize)]
+pub struct MoodPeriod {
+    pub period: String,
+    pub moods: BTreeMap<String, i64>,
+}
+
+#[derive(sqlx::FromRow)]
+struct MoodRow {
+    period: String,
+    mood: Option<String>,
+    count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct StreakRow {
+    start: String,
+    end: String,
+    days: i64,
+}
+
+struct Range {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+// Awal minggu (Senin) dalam SQL: 'weekday 0' maju ke Minggu, lalu mundur 6 hari
+const WEEK_START: &str = "date(l.entry_date, 'weekday 0', '-6 days')";
+
+fn period_expr(bucket: &str) -> &'static str {
+    match bucket {
+        "day" => "l.entry_date",
+        "month" => "strftime('%Y-%m', l.entry_date)",
+        _ => WEEK_START,
+    }
+}
+
+fn parse_query(query: &StatsQuery) -> AppResult<(Range, String, i64)> {
+    let mut errors = Vec::new();
+
+    let mut parse_date = |field: &'static str, raw: Option<&String>| {
+        let raw = raw.filter(|r| !r.trim().is_empty())?;
+        match NaiveDate::parse_from_str(raw.trim(), "%Y-%m-%d") {
+            Ok(date) => Some(date),
+            Err(_) => {
+                errors.push(FieldError::new(field, format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", raw)));
+                None
+            }
+        }
+    };
+    let from = parse_date("from", query.from.as_ref());
+    let to = parse_date("to", query.to.as_ref());
+
+    let to = to.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let from = from.unwrap_or(to - Duration::days(DEFAULT_DAYS - 1));
+    if from > to {
+        errors.push(FieldError::new("to", "Tanggal akhir tidak boleh sebelum tanggal awal"));
+    } else if (to - from).num_days() + 1 > MAX_DAYS {
+        errors.push(FieldError::new("from", format!("Rentang maksimal {} hari", MAX_DAYS)));
+    }
+
+    let bucket = query.bucket.as_deref().unwrap_or("week").trim().to_lowercase();
+    if !BUCKETS.contains(&bucket.as_str()) {
+        errors.push(FieldError::new("bucket", format!("Pilih salah satu: {}", BUCKETS.join(", "))));
+    }
+
+    let top = query.top.unwrap_or(10);
+    if !(1..=100).contains(&top) {
+        errors.push(FieldError::new("top", "Harus 1-100"));
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+    Ok((Range { from, to }, bucket, top))
+}
+
+// SELECT ... FROM log_entries l [join] WHERE <milik user, bukan trash, dalam rentang>
+fn scoped(select: &str, user_id: i64, range: &Range) -> QueryBuilder<'static, Sqlite> {
+    let mut qb = QueryBuilder::<Sqlite>::new(select);
+    qb.push(" WHERE l.user_id = ").push_bind(user_id);
+    qb.push(" AND l.is_deleted = FALSE");
+    qb.push(" AND l.entry_date >= ").push_bind(range.from.format("%Y-%m-%d").to_string());
+    qb.push(" AND l.entry_date <= ").push_bind(range.to.format("%Y-%m-%d").to_string());
+    qb
+}
+
+async fn streaks(pool: &SqlitePool, user_id: i64, today: NaiveDate) -> Result<Streaks, sqlx::Error> {
+    // Gaps & islands: tanggal berurutan punya selisih (julianday - nomor urut) yang sama
+    let islands: Vec<StreakRow> = sqlx::query_as(
+        "SELECT MIN(d) as start, MAX(d) as end, COUNT(*) as days FROM (
+            SELECT d, julianday(d) - ROW_NUMBER() OVER (ORDER BY d) as grp
+            FROM (SELECT DISTINCT entry_date as d FROM log_entries WHERE user_id = ? AND is_deleted = FALSE)
+         ) GROUP BY grp ORDER BY end DESC"
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut result = Streaks::default();
+    if let Some(last) = islands.first() {
+        let last_day = NaiveDate::parse_from_str(&last.end, "%Y-%m-%d").ok();
+        if last_day.is_some_and(|d| d == today || d == today - Duration::days(1)) {
+            result.current = last.days;
+        }
+    }
+    // Streak terpanjang; kalau sama panjang, yang paling baru
+    if let Some(longest) = islands.iter().max_by(|a, b| a.days.cmp(&b.days).then(a.end.cmp(&b.end))) {
+        result.longest = longest.days;
+        result.longest_start = Some(longest.start.clone());
+        result.longest_end = Some(longest.end.clone());
+    }
+    Ok(result)
+}
+
+// GET /api/stats
+pub async fn get_stats(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(query): Query<StatsQuery>,
+) -> AppResult<Json<Stats>> {
+    let (range, bucket, top) = parse_query(&query)?;
+
+    // 1. Total & rata-rata panjang
+    let totals = scoped(
+        "SELECT COUNT(*) as entries, COUNT(DISTINCT l.entry_date) as active_days,
+                COALESCE(AVG(LENGTH(l.content)), 0.0) as avg_length, COALESCE(SUM(LENGTH(l.content)), 0) as total_length
+         FROM log_entries l",
+        user.id, &range,
+    )
+    .build_query_as::<Totals>()
+    .fetch_one(&pool)
+    .await?;
+
+    // 2. Per hari (hari kosong diisi 0 supaya grafik gak bolong)
+    let mut qb = scoped("SELECT l.entry_date as period, COUNT(*) as count FROM log_entries l", user.id, &range);
+    qb.push(" GROUP BY l.entry_date");
+    let counts: BTreeMap<String, i64> = qb.build_query_as::<PeriodCount>().fetch_all(&pool).await?
+        .into_iter()
+        .map(|p| (p.period, p.count))
+        .collect();
+    let per_day = range.from.iter_days()
+        .take_while(|d| *d <= range.to)
+        .map(|d| {
+            let period = d.format("%Y-%m-%d").to_string();
+            let count = counts.get(&period).copied().unwrap_or(0);
+            PeriodCount { period, count }
+        })
+        .collect();
+
+    // 3. Per minggu (Senin - Minggu)
+    let mut qb = scoped(&format!("SELECT {} as period, COUNT(*) as count FROM log_entries l", WEEK_START), user.id, &range);
+    qb.push(" GROUP BY period ORDER BY period ASC");
+    let per_week = qb.build_query_as::<PeriodCount>().fetch_all(&pool).await?;
+
+    // 4. Mood: distribusi total & per periode
+    let mut qb = scoped("SELECT NULLIF(TRIM(l.mood), '') as mood, COUNT(*) as count FROM log_entries l", user.id, &range);
+    qb.push(" GROUP BY 1 ORDER BY count DESC");
+    let mut distribution = qb.build_query_as::<MoodCount>().fetch_all(&pool).await?;
+    for mood in &mut distribution {
+        mood.percent = if totals.entries > 0 {
+            (mood.count as f64 * 1000.0 / totals.entries as f64).round() / 10.0
+        } else {
+            0.0
+        };
+    }
+
+    let mut qb = scoped(
+        &format!("SELECT {} as period, NULLIF(TRIM(l.mood), '') as mood, COUNT(*) as count FROM log_entries l", period_expr(&bucket)),
+        user.id, &range,
+    );
+    qb.push(" GROUP BY 1, 2 ORDER BY 1 ASC");
+    let mut over_time: Vec<MoodPeriod> = Vec::new();
+    for row in qb.build_query_as::<MoodRow>().fetch_all(&pool).await? {
+        let mood = row.mood.unwrap_or_else(|| "None".to_string());
+        match over_time.last_mut() {
+            Some(last) if last.period == row.period => { last.moods.insert(mood, row.count); }
+            _ => over_time.push(MoodPeriod { period: row.period, moods: BTreeMap::from([(mood, row.count)]) }),
+        }
+    }
+
+    // 5. Tag teratas (tags = JSON array; data rusak dilewati, bukan bikin query gagal)
+    let mut qb = scoped(
+        "SELECT j.value as name, COUNT(*) as count
+         FROM log_entries l, json_each(CASE WHEN json_valid(l.tags) THEN l.tags ELSE '[]' END) j",
+        user.id, &range,
+    );
+    qb.push(" AND j.type = 'text' GROUP BY j.value ORDER BY count DESC, name ASC LIMIT ").push_bind(top);
+    let top_tags = qb.build_query_as::<NameCount>().fetch_all(&pool).await?;
+
+    // 6. Kategori & sumber
+    let mut qb = scoped("SELECT COALESCE(l.category, 'General') as name, COUNT(*) as count FROM log_entries l", user.id, &range);
+    qb.push(" GROUP BY 1 ORDER BY count DESC, name ASC LIMIT ").push_bind(top);
+    let categories = qb.build_query_as::<NameCount>().fetch_all(&pool).await?;
+
+    let mut qb = scoped("SELECT COALESCE(l.source, 'Manual') as name, COUNT(*) as count FROM log_entries l", user.id, &range);
+    qb.push(" GROUP BY 1 ORDER BY count DESC, name ASC");
+    let sources = qb.build_query_as::<NameCount>().fetch_all(&pool).await?;
+
+    Ok(Json(Stats {
+        range: StatsRange {
+            from: range.from.format("%Y-%m-%d").to_string(),
+            to: range.to.format("%Y-%m-%d").to_string(),
+            days: (range.to - range.from).num_days() + 1,
+            bucket,
+        },
+        totals,
+        per_day,
+        per_week,
+        streaks: streaks(&pool, user.id, chrono::Local::now().date_naive()).await?,
+        moods: MoodStats { distribution, over_time },
+        top_tags,
+        categories,
+        sources,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER: i64 = 1;
+
+    fn day(date: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap()
+    }
+
+    async fn setup(entries: &[(&str, bool)]) -> SqlitePool {
+        let pool = crate::db::test_pool().await;
+        sqlx::query("CREATE TABLE log_entries (id INTEGER PRIMARY KEY, user_id INTEGER, entry_date DATE, is_deleted BOOLEAN DEFAULT FALSE)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for (date, deleted) in entries {
+            sqlx::query("INSERT INTO log_entries (user_id, entry_date, is_deleted) VALUES (?, ?, ?)")
+                .bind(USER)
+                .bind(date)
+                .bind(deleted)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        pool
+    }
+
+    #[tokio::test]
+    async fn current_streak_may_end_today_or_yesterday() {
+        let pool = setup(&[("2026-10-17", false), ("2026-10-18", false), ("2026-10-18", false), ("2026-10-19", false)]).await;
+
+        assert_eq!(streaks(&pool, USER, day("2026-10-19")).await.unwrap().current, 3);
+        // Hari ini belum nulis, streak kemarin masih dihitung
+        assert_eq!(streaks(&pool, USER, day("2026-10-20")).await.unwrap().current, 3);
+        assert_eq!(streaks(&pool, USER, day("2026-10-21")).await.unwrap().current, 0);
+    }
+
+    #[tokio::test]
+    async fn longest_streak_prefers_the_most_recent_on_ties() {
+        let pool = setup(&[
+            ("2026-09-01", false), ("2026-09-02", false),
+            ("2026-09-10", false), ("2026-09-11", false),
+            ("2026-10-01", false),
+        ]).await;
+
+        let result = streaks(&pool, USER, day("2026-10-19")).await.unwrap();
+        assert_eq!((result.current, result.longest), (0, 2));
+        assert_eq!(result.longest_start.as_deref(), Some("2026-09-10"));
+        assert_eq!(result.longest_end.as_deref(), Some("2026-09-11"));
+    }
+
+    #[tokio::test]
+    async fn deleted_entries_break_the_streak() {
+        let pool = setup(&[("2026-10-17", false), ("2026-10-18", true), ("2026-10-19", false)]).await;
+
+        let result = streaks(&pool, USER, day("2026-10-19")).await.unwrap();
+        assert_eq!((result.current, result.longest), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn no_entries_means_no_streak() {
+        let pool = setup(&[]).await;
+
+        let result = streaks(&pool, USER, day("2026-10-19")).await.unwrap();
+        assert_eq!((result.current, result.longest), (0, 0));
+        assert!(result.longest_start.is_none());
+    }
+}
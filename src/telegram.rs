@@ -0,0 +1,304 @@
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::integrations::{ExternalItem, Integration, IntegrationConfig, PollResult};
+use crate::logbook::{self, CreateLogRequest};
+
+const DEFAULT_BASE_URL: &str = "https://api.telegram.org";
+const LONG_POLL_SECS: u64 = 25;
+
+const HELP_TEXT: &str = "📒 Noty Bot\n\
+Kirim pesan biasa untuk mencatat log.\n\
+/mood <mood> - ubah mood catatan terakhir\n\
+/tag <tag1, tag2> - tambah tag ke catatan terakhir\n\
+/today - lihat catatan hari ini";
+
+// --- STRUKTUR DATA BOT API ---
+
+#[derive(Deserialize)]
+struct TelegramResponse<T> {
+    ok: bool,
+    result: Option<T>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Message {
+    message_id: i64,
+    date: i64, // Unix timestamp
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+// Config: {"base_url": "...", "allowed_chat_ids": [123, 456]}, bot token di kolom api_key.
+// base_url bisa diarahkan ke mock lokal buat testing.
+pub struct TelegramIntegration;
+
+fn base_url(config: &Value) -> String {
+    config.get("base_url")
+        .and_then(Value::as_str)
+        .filter(|u| !u.is_empty())
+        .unwrap_or(DEFAULT_BASE_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn allowed_chat_ids(config: &Value) -> Vec<i64> {
+    config.get("allowed_chat_ids")
+        .and_then(Value::as_array)
+        .map(|ids| ids.iter().filter_map(Value::as_i64).collect())
+        .unwrap_or_default()
+}
+
+fn api_url(config: &IntegrationConfig, method: &str) -> String {
+    format!("{}/bot{}/{}", base_url(&config.config), config.api_key, method)
+}
+
+async fn send_message(config: &IntegrationConfig, chat_id: i64, reply_to: i64, text: &str) {
+    let res = reqwest::Client::new()
+        .post(api_url(config, "sendMessage"))
+        .json(&json!({ "chat_id": chat_id, "text": text, "reply_to_message_id": reply_to }))
+        .send()
+        .await;
+
+    match res {
+        Ok(r) if !r.status().is_success() => println!("⚠️ [telegram] sendMessage gagal: {}", r.status()),
+        Err(e) => println!("❌ [telegram] Gagal koneksi HTTP: {}", e.without_url()),
+        _ => {}
+    }
+}
+
+// Entri terakhir yang dicatat dari chat ini (target /mood & /tag)
+async fn last_entry_id(pool: &SqlitePool, user_id: i64, chat_id: i64) -> Option<i64> {
+    sqlx::query_scalar(
+        "SELECT r.log_entry_id FROM external_refs r JOIN log_entries l ON l.id = r.log_entry_id
+         WHERE r.user_id = ? AND r.service_name = 'telegram' AND r.external_id LIKE ? AND l.is_deleted = FALSE
+         ORDER BY r.id DESC LIMIT 1"
+    )
+    .bind(user_id)
+    .bind(format!("{}:%", chat_id))
+    .fetch_optional(pool)
+    .await
+    .unwrap_or(None)
+}
+
+async fn command_reply(pool: &SqlitePool, user_id: i64, chat_id: i64, text: &str) -> String {
+    let (command, arg) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    // "/mood@NotyBot" di grup -> "/mood"
+    let command = command.split('@').next().unwrap_or(command);
+    let arg = arg.trim();
+
+    match command {
+        "/mood" | "/tag" if arg.is_empty() => format!("⚠️ Format: {} <nilai>", command),
+        "/mood" => set_mood(pool, user_id, chat_id, arg).await,
+        "/tag" => add_tags(pool, user_id, chat_id, arg).await,
+        "/today" => today_summary(pool, user_id).await,
+        _ => HELP_TEXT.to_string(),
+    }
+}
+
+async fn set_mood(pool: &SqlitePool, user_id: i64, chat_id: i64, mood: &str) -> String {
+    let Some(id) = last_entry_id(pool, user_id, chat_id).await else {
+        return "⚠️ Belum ada catatan dari chat ini.".to_string();
+    };
+
+    // Sama dengan validasi CreateLogRequest: hanya mood bawaan / extra_moods di config
+    let Some(mood) = logbook::known_mood(mood) else {
+        return format!("⚠️ Mood '{}' tidak dikenal, pilih dari: {}", mood, logbook::allowed_moods());
+    };
+
+    match sqlx::query("UPDATE log_entries SET mood = ? WHERE id = ?").bind(&mood).bind(id).execute(pool).await {
+        Ok(_) => format!("😊 Mood catatan #{} diubah jadi '{}'", id, mood),
+        Err(e) => format!("❌ Gagal update mood: {}", e),
+    }
+}
+
+async fn add_tags(pool: &SqlitePool, user_id: i64, chat_id: i64, raw: &str) -> String {
+    let Some(id) = last_entry_id(pool, user_id, chat_id).await else {
+        return "⚠️ Belum ada catatan dari chat ini.".to_string();
+    };
+
+    let current: Option<String> = sqlx::query_scalar("SELECT tags FROM log_entries WHERE id = ?")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .unwrap_or(None);
+
+    let mut tags = logbook::parse_tags(current.as_deref());
+    for tag in raw.split([',', ' ']).map(|t| t.trim().trim_start_matches('#')).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    let tags_json = serde_json::to_string(&tags).unwrap_or("[]".to_string());
+    match sqlx::query("UPDATE log_entries SET tags = ? WHERE id = ?").bind(tags_json).bind(id).execute(pool).await {
+        Ok(_) => format!("🏷️ Tag catatan #{}: {}", id, tags.join(", ")),
+        Err(e) => format!("❌ Gagal update tag: {}", e),
+    }
+}
+
+async fn today_summary(pool: &SqlitePool, user_id: i64) -> String {
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let entries: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT id, entry_time, content FROM log_entries WHERE user_id = ? AND entry_date = ? AND is_deleted = FALSE ORDER BY entry_time ASC"
+    )
+    .bind(user_id)
+    .bind(&today)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    if entries.is_empty() {
+        return format!("📭 Belum ada catatan untuk {}.", today);
+    }
+
+    let mut reply = format!("📅 Catatan {} ({}):\n", today, entries.len());
+    for (id, time, content) in entries {
+        let first_line = content.lines().next().unwrap_or_default();
+        let preview: String = first_line.chars().take(80).collect();
+        reply.push_str(&format!("• #{} {} — {}\n", id, time.get(..5).unwrap_or(&time), preview));
+    }
+    reply
+}
+
+#[async_trait]
+impl Integration for TelegramIntegration {
+    fn service_name(&self) -> &'static str {
+        "telegram"
+    }
+
+    // getUpdates sudah nunggu sendiri (long-poll), jadi jeda antar request cukup pendek
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+
+    fn configure(&self, config: Value) -> Result<Value, String> {
+        // Chat ID boleh dikirim sebagai angka atau string
+        let mut chat_ids = Vec::new();
+        if let Some(ids) = config.get("allowed_chat_ids").and_then(Value::as_array) {
+            for id in ids {
+                let parsed = id.as_i64().or_else(|| id.as_str().and_then(|s| s.trim().parse().ok()));
+                match parsed {
+                    Some(id) => chat_ids.push(id),
+                    None => return Err(format!("allowed_chat_ids berisi nilai yang bukan chat ID: {}", id)),
+                }
+            }
+        }
+
+        Ok(json!({ "base_url": base_url(&config), "allowed_chat_ids": chat_ids }))
+    }
+
+    fn validate(&self, config: &IntegrationConfig) -> Result<(), String> {
+        if config.api_key.trim().is_empty() {
+            return Err("Bot token (api_key) belum diisi".to_string());
+        }
+        if allowed_chat_ids(&config.config).is_empty() {
+            return Err("allowed_chat_ids kosong, semua pesan akan ditolak".to_string());
+        }
+        Ok(())
+    }
+
+    async fn poll(&self, _pool: &SqlitePool, config: &IntegrationConfig) -> Result<PollResult, String> {
+        let offset = config.state.get("offset").and_then(Value::as_i64).unwrap_or(0);
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(LONG_POLL_SECS + 10))
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        let res = client.get(api_url(config, "getUpdates"))
+            .query(&[
+                ("offset", offset.to_string()),
+                ("timeout", LONG_POLL_SECS.to_string()),
+                ("allowed_updates", "[\"message\"]".to_string()),
+            ])
+            .send()
+            .await
+            // without_url: URL berisi bot token, jangan sampai masuk last_error
+            .map_err(|e| format!("Gagal koneksi HTTP: {}", e.without_url()))?;
+
+        let data = res.json::<TelegramResponse<Vec<Update>>>().await
+            .map_err(|e| format!("Gagal parsing JSON Telegram: {}", e.without_url()))?;
+        if !data.ok {
+            return Err(format!("Telegram Error: {}", data.description.unwrap_or_default()));
+        }
+        let updates = data.result.unwrap_or_default();
+
+        // Tiap update bawa offset-nya sendiri; poller menyimpannya begitu update itu selesai
+        // (pesan tersimpan / perintah dijalankan), jadi yang gagal dikirim ulang Telegram
+        let next_state = updates.last().map(|last| json!({ "offset": last.update_id + 1 }));
+
+        let allowed = allowed_chat_ids(&config.config);
+        let mut items = Vec::new();
+
+        for update in updates {
+            let Some(message) = update.message else { continue };
+            if message.text.as_deref().map(str::trim).is_none_or(str::is_empty) {
+                continue;
+            }
+
+            if !allowed.contains(&message.chat.id) {
+                println!("🚫 [telegram] Pesan dari chat {} diabaikan (tidak di whitelist)", message.chat.id);
+                continue;
+            }
+
+            items.push(ExternalItem {
+                external_id: format!("{}:{}", message.chat.id, message.message_id),
+                payload: serde_json::to_value(&message).unwrap_or(Value::Null),
+                state: Some(json!({ "offset": update.update_id + 1 })),
+            });
+        }
+
+        Ok(PollResult { items, next_state })
+    }
+
+    // Perintah (/mood, /tag, ...) jalan sesuai urutan pesan, setelah pesan sebelumnya tersimpan
+    async fn handle_command(&self, pool: &SqlitePool, config: &IntegrationConfig, item: &ExternalItem) -> bool {
+        let Ok(message) = serde_json::from_value::<Message>(item.payload.clone()) else { return false };
+        let Some(text) = message.text.as_deref().map(str::trim).filter(|t| t.starts_with('/')) else { return false };
+
+        let reply = command_reply(pool, config.user_id, message.chat.id, text).await;
+        send_message(config, message.chat.id, message.message_id, &reply).await;
+        true
+    }
+
+    fn to_log_entry(&self, item: &ExternalItem) -> Option<CreateLogRequest> {
+        let message: Message = serde_json::from_value(item.payload.clone()).ok()?;
+
+        let sent_at = chrono::DateTime::from_timestamp(message.date, 0)
+            .map(|d| d.with_timezone(&chrono::Local))
+            .unwrap_or_else(chrono::Local::now);
+
+        Some(CreateLogRequest {
+            content: message.text?.trim().to_string(),
+            entry_date: sent_at.format("%Y-%m-%d").to_string(),
+            entry_time: sent_at.format("%H:%M:%S").to_string(),
+            tags: Vec::new(),
+            category: None,
+            mood: None,
+            source: Some("Telegram-Bot".to_string()),
+            template_id: None,
+        })
+    }
+
+    async fn after_import(&self, config: &IntegrationConfig, item: &ExternalItem, log_id: i64) {
+        let Ok(message) = serde_json::from_value::<Message>(item.payload.clone()) else { return };
+        let reply = format!("✅ Tercatat sebagai catatan #{}", log_id);
+        send_message(config, message.chat.id, message.message_id, &reply).await;
+    }
+}
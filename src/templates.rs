@@ -0,0 +1,409 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use chrono::{NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, FieldError, JsonBody};
+use crate::logbook::CreateLogRequest;
+
+const NAME_MAX: usize = 80;
+
+// Template bawaan untuk user baru (nama, deskripsi, isi, tag, kategori)
+const DEFAULT_TEMPLATES: &[(&str, &str, &str, &[&str], &str)] = &[
+    (
+        "Daily Standup",
+        "Kemarin, hari ini, hambatan",
+        "### Standup {{date}}\n\n**Kemarin:** {{prompt:Apa yang selesai kemarin?}}\n\n**Hari ini:** {{prompt:Apa rencana hari ini?}}\n\n**Hambatan:** {{prompt:Ada hambatan?}}",
+        &["standup"],
+        "Kerja",
+    ),
+    (
+        "Meeting Notes",
+        "Catatan rapat & action item",
+        "### Rapat: {{prompt:Topik rapat}}\n*{{date}} {{time}}*\n\n**Peserta:** {{prompt:Siapa saja yang hadir?}}\n\n**Catatan:**\n- \n\n**Action item:**\n- ",
+        &["meeting"],
+        "Kerja",
+    ),
+    (
+        "Incident Report",
+        "Kronologi, dampak & tindak lanjut insiden",
+        "### Insiden: {{prompt:Judul insiden}}\n*Dilaporkan {{date}} {{time}}*\n\n**Dampak:** {{prompt:Apa dampaknya?}}\n\n**Kronologi:**\n- {{time}} \n\n**Akar masalah:** {{prompt:Akar masalah (kalau sudah tahu)}}\n\n**Tindak lanjut:**\n- ",
+        &["incident"],
+        "Kerja",
+    ),
+];
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS templates (
+            id INTEGER PRIMARY KEY,
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            name TEXT NOT NULL COLLATE NOCASE,
+            description TEXT,
+            body TEXT NOT NULL,                -- Markdown + placeholder {{date}}, {{time}}, {{prompt:...}}
+            default_tags TEXT DEFAULT '[]',    -- JSON array, digabung ke tag log saat create_log
+            default_category TEXT,             -- Dipakai kalau log tidak menyebut kategori
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(user_id, name)
+        );"
+    ).execute(pool).await?;
+
+    Ok(())
+}
+
+// Dipanggil lewat users::ensure_defaults; hanya kalau user belum punya template sama sekali
+pub async fn seed_defaults(pool: &SqlitePool, user_id: i64) -> Result<(), sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM templates WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+    if count > 0 {
+        return Ok(());
+    }
+
+    for (name, description, body, tags, category) in DEFAULT_TEMPLATES {
+        sqlx::query(
+            "INSERT INTO templates (user_id, name, description, body, default_tags, default_category) VALUES (?, ?, ?, ?, ?, ?)"
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(description)
+        .bind(body)
+        .bind(serde_json::to_string(tags).unwrap_or("[]".to_string()))
+        .bind(category)
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+// ==========================================
+// MODEL
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Template {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub body: String,
+    #[sqlx(rename = "default_tags")]
+    #[serde(skip)]
+    pub default_tags_json: Option<String>,
+    #[sqlx(skip)]
+    pub default_tags: Vec<String>,
+    pub default_category: Option<String>,
+    #[sqlx(skip)]
+    pub prompts: Vec<String>, // Pertanyaan {{prompt:...}} di body, untuk form di UI
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Template {
+    fn hydrate(mut self) -> Self {
+        self.default_tags = crate::logbook::parse_tags(self.default_tags_json.as_deref());
+        self.prompts = prompts(&self.body);
+        self
+    }
+}
+
+const TEMPLATE_COLUMNS: &str = "id, name, description, body, default_tags, default_category,
+    CAST(created_at AS TEXT) as created_at, CAST(updated_at AS TEXT) as updated_at";
+
+#[derive(Deserialize)]
+pub struct TemplateRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub body: String,
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    pub default_category: Option<String>,
+}
+
+// POST /api/templates/{id}/apply
+#[derive(Deserialize)]
+pub struct ApplyRequest {
+    pub date: Option<String>, // Default hari ini
+    pub time: Option<String>, // Default jam sekarang
+    #[serde(default)]
+    pub answers: HashMap<String, String>, // Jawaban per teks prompt
+}
+
+// Hasil render: siap dikirim ke POST /api/logs (plus template_id)
+#[derive(Serialize)]
+pub struct AppliedTemplate {
+    pub template_id: i64,
+    pub content: String,
+    pub entry_date: String,
+    pub entry_time: String,
+    pub tags: Vec<String>,
+    pub category: Option<String>,
+    pub missing_prompts: Vec<String>, // Prompt tanpa jawaban (diisi kosong)
+}
+
+// ==========================================
+// PLACEHOLDER
+// ==========================================
+
+// Ganti setiap {{...}} lewat `resolve`; placeholder yang tidak dikenal dibiarkan apa adanya
+fn render(body: &str, mut resolve: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(body.len());
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else { break };
+        let key = &rest[start + 2..start + 2 + len];
+        out.push_str(&rest[..start]);
+        match resolve(key.trim()) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[start..start + 2 + len + 2]),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn prompts(body: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    render(body, |key| {
+        if let Some(question) = key.strip_prefix("prompt:").map(str::trim) {
+            if !found.iter().any(|q| q == question) {
+                found.push(question.to_string());
+            }
+        }
+        None
+    });
+    found
+}
+
+// ==========================================
+// HELPER
+// ==========================================
+
+async fn find(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<Template> {
+    sqlx::query_as::<_, Template>(&format!("SELECT {} FROM templates WHERE id = ? AND user_id = ?", TEMPLATE_COLUMNS))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .map(Template::hydrate)
+        .ok_or_else(|| AppError::not_found(format!("Template #{} tidak ditemukan", id)))
+}
+
+fn validate(payload: &mut TemplateRequest) -> AppResult<()> {
+    let mut errors = Vec::new();
+
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        errors.push(FieldError::new("name", "Nama template wajib diisi"));
+    } else if payload.name.chars().count() > NAME_MAX {
+        errors.push(FieldError::new("name", format!("Maksimal {} karakter", NAME_MAX)));
+    }
+
+    let max = crate::logbook::max_content_length();
+    let length = payload.body.trim().chars().count();
+    if length == 0 {
+        errors.push(FieldError::new("body", "Isi template tidak boleh kosong"));
+    } else if length > max {
+        errors.push(FieldError::new("body", format!("Maksimal {} karakter, dapat {}", max, length)));
+    }
+
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+
+    payload.description = payload.description.as_deref().map(str::trim).filter(|d| !d.is_empty()).map(str::to_string);
+    payload.default_tags = payload.default_tags.iter().map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect();
+    payload.default_category = payload.default_category.as_deref().map(str::trim).filter(|c| !c.is_empty()).map(str::to_string);
+    Ok(())
+}
+
+fn name_taken(name: &str) -> AppError {
+    AppError::conflict(format!("Template '{}' sudah ada", name)).with_code("template_exists")
+}
+
+// Dipanggil create_log kalau request menyebut template_id: tag default digabung,
+// kategori default dipakai kalau log tidak menyebut kategori sendiri
+pub async fn apply_defaults(pool: &SqlitePool, user_id: i64, template_id: i64, payload: &mut CreateLogRequest) -> AppResult<()> {
+    let template = find(pool, user_id, template_id).await.map_err(|_| AppError::validation(vec![
+        FieldError::new("template_id", format!("Template #{} tidak ditemukan", template_id)),
+    ]))?;
+
+    for tag in template.default_tags {
+        if !payload.tags.iter().any(|t| t.trim().eq_ignore_ascii_case(&tag)) {
+            payload.tags.push(tag);
+        }
+    }
+    if payload.category.as_deref().is_none_or(|c| c.trim().is_empty()) {
+        payload.category = template.default_category;
+    }
+    Ok(())
+}
+
+// ==========================================
+// API
+// ==========================================
+
+// GET /api/templates
+pub async fn list_templates(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<Template>>> {
+    let templates = sqlx::query_as::<_, Template>(&format!(
+        "SELECT {} FROM templates WHERE user_id = ? ORDER BY name ASC", TEMPLATE_COLUMNS
+    ))
+    .bind(user.id)
+    .fetch_all(&pool)
+    .await?;
+    Ok(Json(templates.into_iter().map(Template::hydrate).collect()))
+}
+
+// GET /api/templates/{id}
+pub async fn get_template(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<Template>> {
+    Ok(Json(find(&pool, user.id, id).await?))
+}
+
+// POST /api/templates
+pub async fn create_template(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(mut payload): JsonBody<TemplateRequest>,
+) -> AppResult<(StatusCode, Json<Template>)> {
+    validate(&mut payload)?;
+
+    let result = sqlx::query(
+        "INSERT INTO templates (user_id, name, description, body, default_tags, default_category) VALUES (?, ?, ?, ?, ?, ?)"
+    )
+    .bind(user.id)
+    .bind(&payload.name)
+    .bind(&payload.description)
+    .bind(&payload.body)
+    .bind(serde_json::to_string(&payload.default_tags).unwrap_or("[]".to_string()))
+    .bind(&payload.default_category)
+    .execute(&pool)
+    .await;
+
+    let id = match result {
+        Ok(r) => r.last_insert_rowid(),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => return Err(name_taken(&payload.name)),
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok((StatusCode::CREATED, Json(find(&pool, user.id, id).await?)))
+}
+
+// PUT /api/templates/{id}
+pub async fn update_template(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(mut payload): JsonBody<TemplateRequest>,
+) -> AppResult<Json<Template>> {
+    find(&pool, user.id, id).await?;
+    validate(&mut payload)?;
+
+    let result = sqlx::query(
+        "UPDATE templates SET name = ?, description = ?, body = ?, default_tags = ?, default_category = ?, updated_at = CURRENT_TIMESTAMP
+         WHERE id = ? AND user_id = ?"
+    )
+    .bind(&payload.name)
+    .bind(&payload.description)
+    .bind(&payload.body)
+    .bind(serde_json::to_string(&payload.default_tags).unwrap_or("[]".to_string()))
+    .bind(&payload.default_category)
+    .bind(id)
+    .bind(user.id)
+    .execute(&pool)
+    .await;
+
+    match result {
+        Ok(_) => Ok(Json(find(&pool, user.id, id).await?)),
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(name_taken(&payload.name)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// DELETE /api/templates/{id}
+pub async fn delete_template(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<StatusCode> {
+    let result = sqlx::query("DELETE FROM templates WHERE id = ? AND user_id = ?")
+        .bind(id)
+        .bind(user.id)
+        .execute(&pool)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Template #{} tidak ditemukan", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /api/templates/{id}/apply -> render placeholder, belum menyimpan log apa pun
+pub async fn apply_template(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<ApplyRequest>,
+) -> AppResult<Json<AppliedTemplate>> {
+    let template = find(&pool, user.id, id).await?;
+
+    let mut errors = Vec::new();
+    let now = chrono::Local::now().naive_local();
+    let date = match payload.date.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        None => now.date(),
+        Some(raw) => NaiveDate::parse_from_str(raw, "%Y-%m-%d").unwrap_or_else(|_| {
+            errors.push(FieldError::new("date", format!("Format tanggal harus YYYY-MM-DD, dapat '{}'", raw)));
+            now.date()
+        }),
+    };
+    let time = match payload.time.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        None => now.time(),
+        Some(raw) => NaiveTime::parse_from_str(raw, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(raw, "%H:%M"))
+            .unwrap_or_else(|_| {
+                errors.push(FieldError::new("time", format!("Format jam harus HH:MM, dapat '{}'", raw)));
+                now.time()
+            }),
+    };
+    if !errors.is_empty() {
+        return Err(AppError::validation(errors));
+    }
+
+    let mut missing_prompts = Vec::new();
+    let content = render(&template.body, |key| match key {
+        "date" => Some(date.format("%Y-%m-%d").to_string()),
+        "time" => Some(time.format("%H:%M").to_string()),
+        _ => {
+            let question = key.strip_prefix("prompt:")?.trim();
+            let answer = payload.answers.get(question).map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+            if answer.is_none() && !missing_prompts.iter().any(|q| q == question) {
+                missing_prompts.push(question.to_string());
+            }
+            Some(answer.unwrap_or_default())
+        }
+    });
+
+    Ok(Json(AppliedTemplate {
+        template_id: template.id,
+        content,
+        entry_date: date.format("%Y-%m-%d").to_string(),
+        entry_time: time.format("%H:%M:%S").to_string(),
+        tags: template.default_tags,
+        category: template.default_category,
+        missing_prompts,
+    }))
+}
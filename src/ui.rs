@@ -0,0 +1,77 @@
+use axum::{
+    http::{header, StatusCode, Uri},
+    response::{IntoResponse, Response},
+};
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::AppError;
+
+// UI React hasil `npm run build`. Dengan feature `embed-ui` file-nya ikut di dalam binary,
+// tanpa feature itu dibaca dari server.ui_dir di disk.
+#[cfg(feature = "embed-ui")]
+#[derive(rust_embed::Embed)]
+#[folder = "ui/dist"]
+struct EmbeddedUi;
+
+const INDEX: &str = "index.html";
+
+// Asset Vite di /assets/ namanya sudah mengandung hash, aman di-cache selamanya
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+// index.html harus selalu dicek ulang supaya deploy baru langsung kepakai
+const REVALIDATE: &str = "no-cache";
+
+pub fn is_embedded() -> bool {
+    cfg!(feature = "embed-ui")
+}
+
+// Tolak path aneh ("..", absolut) supaya gak bisa baca file di luar folder UI
+fn safe_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    path.components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| path.to_path_buf())
+}
+
+async fn load(dir: &Path, path: &Path) -> Option<Vec<u8>> {
+    #[cfg(feature = "embed-ui")]
+    if let Some(file) = EmbeddedUi::get(&path.to_string_lossy()) {
+        return Some(file.data.into_owned());
+    }
+
+    tokio::fs::read(dir.join(path)).await.ok()
+}
+
+fn file_response(path: &Path, body: Vec<u8>) -> Response {
+    let mime = mime_guess::from_path(path).first_or_octet_stream();
+    let cache = if path.starts_with("assets") { IMMUTABLE } else { REVALIDATE };
+
+    ([(header::CONTENT_TYPE, mime.to_string()), (header::CACHE_CONTROL, cache.to_string())], body).into_response()
+}
+
+// Fallback router: file statis UI, selain itu index.html (routing SPA di sisi client)
+pub async fn serve(dir: PathBuf, uri: Uri) -> Response {
+    let path = uri.path();
+
+    // Endpoint API yang gak ada jangan dijawab index.html
+    if path == "/api" || path.starts_with("/api/") {
+        return AppError::not_found(format!("Endpoint {} tidak ditemukan", path)).into_response();
+    }
+
+    let relative = path.trim_start_matches('/');
+    if let Some(file) = safe_path(relative).filter(|p| !p.as_os_str().is_empty()) {
+        if let Some(body) = load(&dir, &file).await {
+            return file_response(&file, body);
+        }
+        // File asset yang hilang = 404 beneran, bukan halaman SPA
+        if file.extension().is_some() {
+            return StatusCode::NOT_FOUND.into_response();
+        }
+    }
+
+    match load(&dir, Path::new(INDEX)).await {
+        Some(body) => file_response(Path::new(INDEX), body),
+        // UI belum di-build: tetap kasih tanda server hidup
+        None if path == "/" => "Noty Logbook System v2.0 Online 🟢".into_response(),
+        None => (StatusCode::NOT_FOUND, "UI belum di-build (jalankan `npm run build` di folder ui)").into_response(),
+    }
+}
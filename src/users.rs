@@ -0,0 +1,150 @@
+use axum::{
+    extract::State,
+    response::Json,
+    Extension,
+    http::StatusCode,
+};
+use sqlx::SqlitePool;
+use serde::{Deserialize, Serialize};
+
+use crate::auth::{self, AuthUser};
+use crate::error::{AppError, AppResult, JsonBody};
+
+// Tabel yang isinya milik satu user
+const OWNED_TABLES: &[&str] = &["log_entries", "ai_personas", "integrations", "app_settings"];
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    crate::db::add_column_if_missing(pool, "users", "is_admin", "BOOLEAN DEFAULT FALSE").await?;
+
+    for table in OWNED_TABLES {
+        crate::db::add_column_if_missing(pool, table, "user_id", "INTEGER REFERENCES users(id)").await?;
+    }
+
+    // Data dari versi single-user dipindah ke user default
+    let mut orphaned = false;
+    for table in OWNED_TABLES {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE user_id IS NULL", table))
+            .fetch_one(pool)
+            .await?;
+        orphaned |= count > 0;
+    }
+    if orphaned {
+        let user_id = default_user(pool).await?;
+        for table in OWNED_TABLES {
+            sqlx::query(&format!("UPDATE {} SET user_id = ? WHERE user_id IS NULL", table))
+                .bind(user_id)
+                .execute(pool)
+                .await?;
+        }
+        println!("👤 Data lama dipindahkan ke user #{}.", user_id);
+    }
+
+    sqlx::query("CREATE INDEX IF NOT EXISTS idx_log_entries_user_date ON log_entries (user_id, entry_date)")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+// User pertama, atau user placeholder (tanpa password) yang nanti diklaim lewat /api/auth/setup
+async fn default_user(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    let existing: Option<i64> = sqlx::query_scalar("SELECT id FROM users ORDER BY id ASC LIMIT 1")
+        .fetch_optional(pool)
+        .await?;
+    if let Some(id) = existing {
+        return Ok(id);
+    }
+
+    let id = sqlx::query("INSERT INTO users (username, password_hash, is_admin) VALUES ('default', '', TRUE)")
+        .execute(pool)
+        .await?
+        .last_insert_rowid();
+    Ok(id)
+}
+
+// Settings & persona bawaan untuk user yang belum punya
+pub async fn ensure_defaults(pool: &SqlitePool, user_id: i64) -> Result<(), sqlx::Error> {
+    crate::settings::seed_defaults(pool, user_id).await?;
+    crate::personas::seed_defaults(pool, user_id).await?;
+    crate::categories::seed_defaults(pool, user_id).await?;
+    crate::templates::seed_defaults(pool, user_id).await?;
+    Ok(())
+}
+
+// ==========================================
+// API KELOLA USER (khusus admin)
+// ==========================================
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct User {
+    pub id: i64,
+    pub username: String,
+    pub is_admin: bool,
+    pub created_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct CreateUserRequest {
+    pub username: String,
+    pub password: String,
+    pub is_admin: Option<bool>,
+}
+
+// `action` melengkapi pesan error, misal "mengelola user" -> "Hanya admin yang boleh mengelola user"
+pub fn require_admin(user: &AuthUser, action: &str) -> AppResult<()> {
+    if !user.is_admin {
+        return Err(AppError::forbidden(format!("Hanya admin yang boleh {}", action)));
+    }
+    Ok(())
+}
+
+const USER_COLUMNS: &str = "id, username, COALESCE(is_admin, FALSE) as is_admin, CAST(created_at AS TEXT) as created_at";
+
+// GET semua user
+pub async fn list_users(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<User>>> {
+    require_admin(&user, "mengelola user")?;
+
+    let users = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE password_hash != '' ORDER BY id ASC", USER_COLUMNS))
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(Json(users))
+}
+
+// CREATE user baru (logbook, settings & persona-nya terpisah dari user lain)
+pub async fn create_user(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<CreateUserRequest>,
+) -> AppResult<(StatusCode, Json<User>)> {
+    require_admin(&user, "mengelola user")?;
+    let username = auth::check_credentials(&payload.username, &payload.password)?;
+
+    let taken: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM users WHERE username = ?)")
+        .bind(&username)
+        .fetch_one(&pool)
+        .await?;
+    if taken {
+        return Err(AppError::conflict(format!("Username '{}' sudah dipakai", username)));
+    }
+
+    let id = sqlx::query("INSERT INTO users (username, password_hash, is_admin) VALUES (?, ?, ?)")
+        .bind(&username)
+        .bind(auth::hash_password(&payload.password)?)
+        .bind(payload.is_admin.unwrap_or(false))
+        .execute(&pool)
+        .await?
+        .last_insert_rowid();
+
+    ensure_defaults(&pool, id).await?;
+
+    let created = sqlx::query_as::<_, User>(&format!("SELECT {} FROM users WHERE id = ?", USER_COLUMNS))
+        .bind(id)
+        .fetch_one(&pool)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
@@ -0,0 +1,590 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+    Extension,
+};
+use sqlx::SqlitePool;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::auth::AuthUser;
+use crate::error::{AppError, AppResult, JsonBody};
+use crate::integrations;
+
+// Target outbound disimpan di tabel `integrations` dengan service_name di bawah ini.
+// URL webhook disimpan di kolom api_key karena URL Discord itu sendiri adalah kredensial.
+const TARGET_SERVICES: &[&str] = &["discord", "webhook"];
+const BASE_EVENTS: &[&str] = &["entry_created", "daily_summary", "reminder_due"];
+
+// Retry: 30 detik, 1 menit, 2 menit, 4 menit, lalu menyerah
+const MAX_ATTEMPTS: i64 = 5;
+const BASE_BACKOFF_SECS: i64 = 30;
+
+// Discord menolak content > 2000 karakter
+const DISCORD_LIMIT: usize = 1900;
+
+pub async fn init_schema(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS webhook_deliveries (
+            id INTEGER PRIMARY KEY,
+            target_id INTEGER NOT NULL REFERENCES integrations(id),
+            event TEXT NOT NULL,
+            payload TEXT NOT NULL,         -- Envelope JSON: {event, created_at, data}
+
+            status TEXT DEFAULT 'pending', -- 'pending', 'delivered', 'failed'
+            attempts INTEGER DEFAULT 0,
+            response_status INTEGER,
+            last_error TEXT,
+
+            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            next_attempt_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            delivered_at DATETIME
+        );"
+    ).execute(pool).await?;
+
+    Ok(())
+}
+
+// Event yang boleh dipasang di filter target: event dasar + "<service>_import" tiap integrasi
+fn known_events() -> Vec<String> {
+    BASE_EVENTS.iter()
+        .map(|e| e.to_string())
+        .chain(integrations::registry().iter().map(|i| format!("{}_import", i.service_name())))
+        .collect()
+}
+
+// ==========================================
+// ANTRIAN EVENT
+// ==========================================
+
+fn envelope(event: &str, data: Value) -> String {
+    json!({
+        "event": event,
+        "created_at": chrono::Local::now().to_rfc3339(),
+        "data": data,
+    }).to_string()
+}
+
+// Masukkan event ke antrian semua target aktif milik user yang filternya cocok.
+// Pengiriman sebenarnya dikerjakan worker, jadi aman dipanggil dari handler.
+pub async fn notify(pool: &SqlitePool, user_id: i64, event: &str, data: Value) {
+    let result = sqlx::query(
+        "INSERT INTO webhook_deliveries (target_id, event, payload)
+         SELECT id, ?, ? FROM integrations
+         WHERE user_id = ? AND service_name IN ('discord', 'webhook') AND is_active = TRUE
+           AND EXISTS (SELECT 1 FROM json_each(integrations.config_json, '$.events') WHERE value = ?)"
+    )
+    .bind(event)
+    .bind(envelope(event, data))
+    .bind(user_id)
+    .bind(event)
+    .execute(pool)
+    .await;
+
+    if let Err(e) = result {
+        println!("❌ [Webhook] Gagal antri event {}: {}", event, e);
+    }
+}
+
+async fn enqueue(pool: &SqlitePool, target_id: i64, event: &str, data: Value) -> Result<(), sqlx::Error> {
+    sqlx::query("INSERT INTO webhook_deliveries (target_id, event, payload) VALUES (?, ?, ?)")
+        .bind(target_id)
+        .bind(event)
+        .bind(envelope(event, data))
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// ==========================================
+// WORKER PENGIRIMAN
+// ==========================================
+
+#[derive(sqlx::FromRow)]
+struct DueDelivery {
+    id: i64,
+    attempts: i64,
+    payload: String,
+    service_name: String,
+    url: Option<String>,
+}
+
+// Ubah envelope jadi teks pendek untuk Discord
+fn discord_content(envelope: &Value) -> String {
+    let event = envelope["event"].as_str().unwrap_or_default();
+    let data = &envelope["data"];
+
+    let text = match event {
+        "entry_created" => format!(
+            "📝 **Catatan baru** ({} {}, {})\n{}",
+            data["entry_date"].as_str().unwrap_or_default(),
+            data["entry_time"].as_str().unwrap_or_default(),
+            data["source"].as_str().unwrap_or("Manual"),
+            data["content"].as_str().unwrap_or_default(),
+        ),
+        "daily_summary" => {
+            let mut text = format!(
+                "📅 **Ringkasan {}**: {} catatan",
+                data["date"].as_str().unwrap_or_default(),
+                data["count"].as_i64().unwrap_or(0),
+            );
+            for entry in data["entries"].as_array().into_iter().flatten() {
+                text.push_str(&format!(
+                    "\n• {} — {}",
+                    entry["entry_time"].as_str().unwrap_or_default(),
+                    entry["preview"].as_str().unwrap_or_default(),
+                ));
+            }
+            text
+        }
+        "reminder_due" => {
+            let mut text = format!(
+                "⏰ **Pengingat**: {} ({})",
+                data["title"].as_str().unwrap_or_default(),
+                data["due_at"].as_str().unwrap_or_default(),
+            );
+            if let Some(note) = data["note"].as_str() {
+                text.push_str(&format!("\n{}", note));
+            }
+            text
+        }
+        _ if event.ends_with("_import") => format!(
+            "📦 **{}**: {} item baru masuk logbook",
+            data["service"].as_str().unwrap_or_default(),
+            data["count"].as_i64().unwrap_or(0),
+        ),
+        _ => format!("🔔 {}", event),
+    };
+
+    if text.chars().count() > DISCORD_LIMIT {
+        let truncated: String = text.chars().take(DISCORD_LIMIT).collect();
+        format!("{}…", truncated)
+    } else {
+        text
+    }
+}
+
+async fn deliver(client: &reqwest::Client, delivery: &DueDelivery) -> (Option<u16>, Result<(), String>) {
+    let url = crate::crypto::reveal(delivery.url.clone()).unwrap_or_default();
+    if url.is_empty() {
+        return (None, Err("URL target kosong".to_string()));
+    }
+
+    let envelope: Value = serde_json::from_str(&delivery.payload).unwrap_or(Value::Null);
+    let request = if delivery.service_name == "discord" {
+        client.post(&url).json(&json!({ "content": discord_content(&envelope) }))
+    } else {
+        client.post(&url)
+            .header("X-Noty-Event", envelope["event"].as_str().unwrap_or_default())
+            .json(&envelope)
+    };
+
+    match request.send().await {
+        Ok(res) => {
+            let status = res.status();
+            if status.is_success() {
+                (Some(status.as_u16()), Ok(()))
+            } else {
+                let body: String = res.text().await.unwrap_or_default().chars().take(500).collect();
+                (Some(status.as_u16()), Err(format!("HTTP {}: {}", status, body)))
+            }
+        }
+        // without_url: URL webhook adalah rahasia, jangan masuk log pengiriman
+        Err(e) => (None, Err(format!("Gagal koneksi HTTP: {}", e.without_url()))),
+    }
+}
+
+pub async fn start_delivery_worker(pool: SqlitePool) {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(15))
+        .build()
+        .unwrap_or_default();
+    println!("📮 Webhook Delivery Worker Started...");
+
+    loop {
+        // Target yang dinonaktifkan tidak dikirimi lagi, termasuk antrean yang masuk sebelumnya
+        let due: Vec<DueDelivery> = sqlx::query_as(
+            "SELECT d.id, d.attempts, d.payload, i.service_name, i.api_key as url
+             FROM webhook_deliveries d JOIN integrations i ON i.id = d.target_id
+             WHERE d.status = 'pending' AND d.next_attempt_at <= CURRENT_TIMESTAMP AND i.is_active = TRUE
+             ORDER BY d.id ASC LIMIT 20"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_default();
+
+        for delivery in due {
+            let attempts = delivery.attempts + 1;
+            let (response_status, result) = deliver(&client, &delivery).await;
+
+            let update = match result {
+                Ok(()) => sqlx::query(
+                    "UPDATE webhook_deliveries SET status = 'delivered', attempts = ?, response_status = ?,
+                            last_error = NULL, delivered_at = CURRENT_TIMESTAMP WHERE id = ?"
+                )
+                .bind(attempts)
+                .bind(response_status)
+                .bind(delivery.id),
+                Err(e) => {
+                    println!("⚠️ [Webhook] Delivery #{} gagal (percobaan {}): {}", delivery.id, attempts, e);
+                    let status = if attempts >= MAX_ATTEMPTS { "failed" } else { "pending" };
+                    let backoff = BASE_BACKOFF_SECS * 2_i64.pow((attempts - 1) as u32);
+                    sqlx::query(
+                        "UPDATE webhook_deliveries SET status = ?, attempts = ?, response_status = ?, last_error = ?,
+                                next_attempt_at = datetime('now', ?) WHERE id = ?"
+                    )
+                    .bind(status)
+                    .bind(attempts)
+                    .bind(response_status)
+                    .bind(e)
+                    .bind(format!("+{} seconds", backoff))
+                    .bind(delivery.id)
+                }
+            };
+            let _ = update.execute(&pool).await;
+        }
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    }
+}
+
+// ==========================================
+// RINGKASAN HARIAN
+// ==========================================
+
+#[derive(sqlx::FromRow)]
+struct SummaryTarget {
+    id: i64,
+    user_id: i64,
+    config_json: Option<String>,
+    state_json: Option<String>,
+}
+
+async fn daily_summary_data(pool: &SqlitePool, user_id: i64, date: &str) -> Value {
+    let entries: Vec<(i64, String, String, String)> = sqlx::query_as(
+        "SELECT id, entry_time, content, source FROM log_entries WHERE user_id = ? AND entry_date = ? AND is_deleted = FALSE ORDER BY entry_time ASC"
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_all(pool)
+    .await
+    .unwrap_or_default();
+
+    let items: Vec<Value> = entries.iter()
+        .map(|(id, time, content, source)| json!({
+            "id": id,
+            "entry_time": time,
+            "source": source,
+            "preview": content.lines().next().unwrap_or_default().chars().take(120).collect::<String>(),
+        }))
+        .collect();
+
+    json!({ "date": date, "count": items.len(), "entries": items })
+}
+
+pub async fn start_daily_summary(pool: SqlitePool) {
+    loop {
+        let targets: Vec<SummaryTarget> = sqlx::query_as(
+            "SELECT id, user_id, config_json, state_json FROM integrations
+             WHERE service_name IN ('discord', 'webhook') AND is_active = TRUE AND user_id IS NOT NULL
+               AND EXISTS (SELECT 1 FROM json_each(integrations.config_json, '$.events') WHERE value = 'daily_summary')"
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap_or_default();
+
+        let now = chrono::Local::now();
+        let today = now.format("%Y-%m-%d").to_string();
+
+        for target in targets {
+            let config: Value = target.config_json.and_then(|c| serde_json::from_str(&c).ok()).unwrap_or(Value::Null);
+            let state: Value = target.state_json.and_then(|s| serde_json::from_str(&s).ok()).unwrap_or(json!({}));
+
+            // summary_time wajib saat target dibuat; data rusak dilewati, jangan dikirim tengah malam
+            let Some(summary_time) = config["summary_time"].as_str()
+                .and_then(|t| chrono::NaiveTime::parse_from_str(t, "%H:%M").ok()) else {
+                continue;
+            };
+            let already_sent = state["last_summary_date"].as_str() == Some(today.as_str());
+
+            if now.time() < summary_time || already_sent {
+                continue;
+            }
+
+            let data = daily_summary_data(&pool, target.user_id, &today).await;
+            if enqueue(&pool, target.id, "daily_summary", data).await.is_ok() {
+                let _ = integrations::save_state(&pool, target.id, &json!({ "last_summary_date": today })).await;
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+// ==========================================
+// API TARGET & LOG PENGIRIMAN
+// ==========================================
+
+#[derive(Serialize)]
+pub struct WebhookTarget {
+    pub id: i64,
+    pub service: String,
+    pub name: String,
+    pub url_preview: String, // Cuma scheme + host, URL lengkap = rahasia
+    pub events: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary_time: Option<String>,
+    pub is_active: bool,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookTargetRequest {
+    pub service: Option<String>, // 'discord' | 'webhook', wajib saat create
+    pub name: Option<String>,
+    pub url: Option<String>,     // Kosong = jangan timpa URL lama
+    pub events: Option<Vec<String>>,
+    pub summary_time: Option<String>, // HH:MM, jam kirim daily_summary (wajib kalau event itu dipilih)
+    pub is_active: Option<bool>,
+}
+
+#[derive(sqlx::FromRow)]
+struct TargetRow {
+    id: i64,
+    service_name: String,
+    api_key: Option<String>,
+    config_json: Option<String>,
+    is_active: Option<bool>,
+}
+
+impl From<TargetRow> for WebhookTarget {
+    fn from(row: TargetRow) -> Self {
+        let config: Value = row.config_json.and_then(|c| serde_json::from_str(&c).ok()).unwrap_or(Value::Null);
+        let url_preview = crate::crypto::reveal(row.api_key)
+            .and_then(|u| reqwest::Url::parse(&u).ok())
+            .map(|u| format!("{}://{}", u.scheme(), u.host_str().unwrap_or_default()))
+            .unwrap_or_default();
+
+        WebhookTarget {
+            id: row.id,
+            service: row.service_name,
+            name: config["name"].as_str().unwrap_or_default().to_string(),
+            url_preview,
+            events: serde_json::from_value(config["events"].clone()).unwrap_or_default(),
+            summary_time: config["summary_time"].as_str().map(str::to_string),
+            is_active: row.is_active.unwrap_or(false),
+        }
+    }
+}
+
+async fn fetch_target(pool: &SqlitePool, user_id: i64, id: i64) -> AppResult<TargetRow> {
+    sqlx::query_as::<_, TargetRow>(
+        "SELECT id, service_name, api_key, config_json, is_active FROM integrations
+         WHERE id = ? AND user_id = ? AND service_name IN ('discord', 'webhook')"
+    )
+    .bind(id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or(AppError::not_found(format!("Target webhook #{} tidak ditemukan", id)))
+}
+
+fn validate_url(url: &str) -> AppResult<()> {
+    match reqwest::Url::parse(url) {
+        Ok(u) if u.scheme() == "http" || u.scheme() == "https" => Ok(()),
+        _ => Err(AppError::bad_request(format!("URL webhook tidak valid: '{}'", url))),
+    }
+}
+
+fn build_config(name: String, events: Vec<String>, summary_time: Option<String>) -> AppResult<String> {
+    let known = known_events();
+    if let Some(unknown) = events.iter().find(|e| !known.contains(e)) {
+        return Err(AppError::bad_request(format!("Event '{}' tidak dikenal, pilih dari: {}", unknown, known.join(", "))));
+    }
+    match &summary_time {
+        Some(time) if chrono::NaiveTime::parse_from_str(time, "%H:%M").is_err() => {
+            return Err(AppError::bad_request(format!("summary_time harus berformat HH:MM, dapat '{}'", time)));
+        }
+        None if events.iter().any(|e| e == "daily_summary") => {
+            return Err(AppError::bad_request("summary_time (HH:MM) wajib diisi untuk event daily_summary"));
+        }
+        _ => {}
+    }
+
+    let mut config = json!({ "name": name, "events": events });
+    if let Some(time) = summary_time {
+        config["summary_time"] = json!(time);
+    }
+    Ok(config.to_string())
+}
+
+// GET semua target
+pub async fn list_targets(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+) -> AppResult<Json<Vec<WebhookTarget>>> {
+    let rows = sqlx::query_as::<_, TargetRow>(
+        "SELECT id, service_name, api_key, config_json, is_active FROM integrations
+         WHERE user_id = ? AND service_name IN ('discord', 'webhook') ORDER BY id ASC"
+    )
+    .bind(user.id)
+    .fetch_all(&pool)
+    .await?;
+
+    Ok(Json(rows.into_iter().map(WebhookTarget::from).collect()))
+}
+
+// CREATE target baru
+pub async fn create_target(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    JsonBody(payload): JsonBody<WebhookTargetRequest>,
+) -> AppResult<(StatusCode, Json<WebhookTarget>)> {
+    let service = payload.service.unwrap_or_default();
+    if !TARGET_SERVICES.contains(&service.as_str()) {
+        return Err(AppError::bad_request(format!("service harus salah satu dari: {}", TARGET_SERVICES.join(", "))));
+    }
+    let url = payload.url.unwrap_or_default().trim().to_string();
+    validate_url(&url)?;
+
+    let config = build_config(
+        payload.name.unwrap_or(service.clone()),
+        payload.events.unwrap_or_default(),
+        payload.summary_time,
+    )?;
+
+    let id = sqlx::query(
+        "INSERT INTO integrations (user_id, service_name, api_key, config_json, is_active) VALUES (?, ?, ?, ?, ?)"
+    )
+    .bind(user.id)
+    .bind(&service)
+    .bind(crate::crypto::encrypt(&url))
+    .bind(config)
+    .bind(payload.is_active.unwrap_or(true))
+    .execute(&pool)
+    .await?
+    .last_insert_rowid();
+
+    Ok((StatusCode::CREATED, Json(fetch_target(&pool, user.id, id).await?.into())))
+}
+
+// UPDATE target (field yang gak dikirim tetap pakai nilai lama)
+pub async fn update_target(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+    JsonBody(payload): JsonBody<WebhookTargetRequest>,
+) -> AppResult<Json<WebhookTarget>> {
+    let current = WebhookTarget::from(fetch_target(&pool, user.id, id).await?);
+
+    let config = build_config(
+        payload.name.unwrap_or(current.name),
+        payload.events.unwrap_or(current.events),
+        payload.summary_time.or(current.summary_time),
+    )?;
+    let is_active = payload.is_active.unwrap_or(current.is_active);
+
+    match payload.url.map(|u| u.trim().to_string()).filter(|u| !u.is_empty()) {
+        Some(url) => {
+            validate_url(&url)?;
+            sqlx::query("UPDATE integrations SET api_key = ?, config_json = ?, is_active = ? WHERE id = ?")
+                .bind(crate::crypto::encrypt(&url)).bind(config).bind(is_active).bind(id)
+                .execute(&pool).await?;
+        }
+        None => {
+            sqlx::query("UPDATE integrations SET config_json = ?, is_active = ? WHERE id = ?")
+                .bind(config).bind(is_active).bind(id)
+                .execute(&pool).await?;
+        }
+    }
+
+    Ok(Json(fetch_target(&pool, user.id, id).await?.into()))
+}
+
+// DELETE target beserta log pengirimannya
+pub async fn delete_target(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<String>> {
+    fetch_target(&pool, user.id, id).await?;
+
+    sqlx::query("DELETE FROM webhook_deliveries WHERE target_id = ?").bind(id).execute(&pool).await?;
+    sqlx::query("DELETE FROM integrations WHERE id = ?").bind(id).execute(&pool).await?;
+
+    Ok(Json(format!("Target webhook #{} dihapus", id)))
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryFilter {
+    pub status: Option<String>,   // 'pending', 'delivered', 'failed'
+    pub target_id: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, sqlx::FromRow)]
+pub struct Delivery {
+    pub id: i64,
+    pub target_id: i64,
+    pub event: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i64,
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+    pub next_attempt_at: Option<String>,
+    pub delivered_at: Option<String>,
+}
+
+// GET log pengiriman (buat inspeksi delivery yang gagal)
+pub async fn list_deliveries(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Query(params): Query<DeliveryFilter>,
+) -> AppResult<Json<Vec<Delivery>>> {
+    let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+        "SELECT id, target_id, event, payload, status, attempts, response_status, last_error,
+                CAST(created_at AS TEXT) as created_at, CAST(next_attempt_at AS TEXT) as next_attempt_at,
+                CAST(delivered_at AS TEXT) as delivered_at
+         FROM webhook_deliveries WHERE target_id IN (SELECT id FROM integrations WHERE user_id = "
+    );
+    qb.push_bind(user.id).push(")");
+    if let Some(status) = params.status {
+        qb.push(" AND status = ").push_bind(status);
+    }
+    if let Some(target_id) = params.target_id {
+        qb.push(" AND target_id = ").push_bind(target_id);
+    }
+    qb.push(" ORDER BY id DESC LIMIT ").push_bind(params.limit.unwrap_or(100).clamp(1, 500));
+
+    let deliveries = qb.build_query_as::<Delivery>()
+        .fetch_all(&pool)
+        .await?;
+
+    Ok(Json(deliveries))
+}
+
+// REPLAY: kirim ulang delivery (biasanya yang 'failed') dari awal
+pub async fn replay_delivery(
+    State(pool): State<SqlitePool>,
+    Extension(user): Extension<AuthUser>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<String>> {
+    let result = sqlx::query(
+        "UPDATE webhook_deliveries SET status = 'pending', attempts = 0, last_error = NULL,
+                next_attempt_at = CURRENT_TIMESTAMP
+         WHERE id = ? AND target_id IN (SELECT id FROM integrations WHERE user_id = ?)"
+    )
+    .bind(id)
+    .bind(user.id)
+    .execute(&pool)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found(format!("Delivery #{} tidak ditemukan", id)));
+    }
+
+    Ok(Json(format!("Delivery #{} dijadwalkan ulang", id)))
+}
@@ -6,6 +6,18 @@ import {
 } from 'lucide-react'
 import ReactMarkdown from 'react-markdown'
 
+// Relatif: UI disajikan langsung oleh server Noty (atau lewat proxy Vite saat development)
+const API_BASE = import.meta.env.VITE_API_BASE || ''
+
+// Semua request bawa cookie session
+const api = (path, options = {}) => fetch(`${API_BASE}${path}`, { credentials: 'include', ...options })
+
+// Body error dari server: {"error": {"code": "...", "message": "..."}}
+const errorMessage = async (res) => {
+  const data = await res.json().catch(() => null)
+  return data?.error?.message || `Request gagal (${res.status})`
+}
+
 function App() {
   const [view, setView] = useState('timeline') 
   const [logs, setLogs] = useState([])
@@ -30,6 +42,11 @@ const [githubConfig, setGithubConfig] = useState({
   })
   const [msg, setMsg] = useState('')
 
+  // --- STATE AUTH ---
+  const [auth, setAuth] = useState({ checked: false, authenticated: false, setup_required: false, username: null })
+  const [loginForm, setLoginForm] = useState({ username: '', password: '' })
+  const [loginError, setLoginError] = useState('')
+
   // --- STATE NEW ENTRY ---
   const [newEntry, setNewEntry] = useState({
     content: '',
@@ -43,50 +60,65 @@ const [githubConfig, setGithubConfig] = useState({
   // --- API FETCHERS ---
   const fetchLogs = async () => {
     try {
-      const res = await fetch('http://localhost:3000/api/logs')
+      const res = await api('/api/logs?api_version=2')
       setLogs(await res.json())
     } catch (e) { console.error(e) }
   }
 
   const fetchPersonas = async () => {
     try {
-      const res = await fetch('http://localhost:3000/api/personas')
+      const res = await api('/api/personas')
       setPersonas(await res.json())
     } catch (e) { console.error(e) }
   }
 
 const fetchGithubConfig = async () => {
   try {
-    const res = await fetch('http://localhost:3000/api/integrations/github')
+    const res = await api('/api/integrations/github')
     const data = await res.json()
     setGithubConfig({
-      repo_name: data.repo_name,
+      repo_name: data.config.repo_name || '',
       token: '', // token asli tidak ditampilkan
       is_active: data.is_active,
-      is_token_set: data.is_token_set
+      is_token_set: data.is_api_key_set
     })
   } catch (e) { console.error(e) }
 }
 
+const applySettings = (data) => {
+    setSettingsConfig({
+      username: data.username,
+      // FIX: Kalau data dari DB kosong, langsung isi default 'gemini-3-flash-preview'
+      ai_model_name: data.ai_model_name || 'gemini-3-flash-preview', 
+      ai_api_key: '', 
+      is_key_set: data.is_api_key_set
+    })
+  }
+
 const fetchSettings = async () => {
     try {
-      const res = await fetch('http://localhost:3000/api/settings')
-      const data = await res.json()
-      setSettingsConfig({
-        username: data.username,
-        // FIX: Kalau data dari DB kosong, langsung isi default 'gemini-3-flash-preview'
-        ai_model_name: data.ai_model_name || 'gemini-3-flash-preview', 
-        ai_api_key: '', 
-        is_key_set: data.is_api_key_set
-      })
+      const res = await api('/api/settings')
+      applySettings(await res.json())
+    } catch (e) { console.error(e) }
+  }
+
+  const fetchAuth = async () => {
+    try {
+      const res = await api('/api/auth/status')
+      setAuth({ checked: true, ...(await res.json()) })
     } catch (e) { console.error(e) }
   }
 
   // Initial Load
   useEffect(() => {
+    fetchAuth()
+  }, [])
+
+  useEffect(() => {
+    if (!auth.authenticated) return
     fetchLogs()
     fetchPersonas()
-  }, [])
+  }, [auth.authenticated])
 
   // Load Settings saat masuk menu Settings
   useEffect(() => {
@@ -96,14 +128,41 @@ const fetchSettings = async () => {
 
   // --- ACTIONS ---
 
+  const handleLogin = async (e) => {
+    e.preventDefault()
+    setLoginError('')
+    const res = await api(auth.setup_required ? '/api/auth/setup' : '/api/auth/login', {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify(loginForm)
+    })
+    if (!res.ok) {
+      setLoginError(await errorMessage(res))
+      return
+    }
+    setLoginForm({ username: '', password: '' })
+    fetchAuth()
+  }
+
+  const handleLogout = async () => {
+    await api('/api/auth/logout', { method: 'POST' })
+    setLogs([])
+    fetchAuth()
+  }
+
   const saveGithub = async () => {
   setLoading(true)
   try {
-    await fetch('http://localhost:3000/api/integrations/github', {
-      method: 'POST',
+    const res = await api('/api/integrations/github', {
+      method: 'PUT',
       headers: { 'Content-Type': 'application/json' },
-      body: JSON.stringify(githubConfig)
+      body: JSON.stringify({
+        config: { repo_name: githubConfig.repo_name },
+        api_key: githubConfig.token,
+        is_active: githubConfig.is_active
+      })
     })
+    if (!res.ok) throw new Error(await errorMessage(res))
     setMsg('Integrasi GitHub Tersimpan! ✅')
     fetchGithubConfig()
     setTimeout(() => setMsg(''), 3000)
@@ -114,13 +173,14 @@ const fetchSettings = async () => {
   const saveSettings = async () => {
     setLoading(true)
     try {
-      await fetch('http://localhost:3000/api/settings', {
+      const res = await api('/api/settings', {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify(settingsConfig)
       })
+      if (!res.ok) throw new Error(await errorMessage(res))
+      applySettings(await res.json()) // Server balikin settings terbaru (termasuk status key)
       setMsg('Pengaturan tersimpan! ✅')
-      fetchSettings() // Refresh status key
       setTimeout(() => setMsg(''), 3000)
     } catch (e) { setMsg('Gagal simpan ❌') }
     finally { setLoading(false) }
@@ -128,8 +188,10 @@ const fetchSettings = async () => {
 
   const activatePersona = async (id) => {
     try {
-      await fetch(`http://localhost:3000/api/personas/${id}/activate`, { method: 'POST' })
-      fetchPersonas()
+      const res = await api(`/api/personas/${id}/activate`, { method: 'POST' })
+      if (!res.ok) throw new Error(await errorMessage(res))
+      const active = await res.json()
+      setPersonas(prev => prev.map(p => ({ ...p, is_active: p.id === active.id })))
     } catch (e) { console.error(e) }
   }
 
@@ -139,13 +201,17 @@ const fetchSettings = async () => {
     const tagsArray = newEntry.tags.split(',').map(t => t.trim()).filter(t => t)
 
     try {
-      await fetch('http://localhost:3000/api/logs', {
+      const res = await api('/api/logs?api_version=2', {
         method: 'POST',
         headers: { 'Content-Type': 'application/json' },
         body: JSON.stringify({ ...newEntry, tags: tagsArray })
       })
+      if (!res.ok) throw new Error(await errorMessage(res))
+      const created = await res.json()
+      // Sisipkan entry baru tanpa refetch, urutan sama dengan server (terbaru dulu)
+      const sortKey = (log) => `${log.entry_date} ${log.entry_time}`
+      setLogs(prev => [created, ...prev].sort((a, b) => sortKey(b).localeCompare(sortKey(a))))
       setView('timeline')
-      fetchLogs()
       setNewEntry({
         content: '',
         entry_date: new Date().toISOString().split('T')[0],
@@ -154,14 +220,14 @@ const fetchSettings = async () => {
         category: 'General',
         mood: 'Neutral'
       })
-    } catch (e) { alert("Gagal menyimpan log") }
+    } catch (e) { alert(`Gagal menyimpan log: ${e.message}`) }
     finally { setLoading(false) }
   }
 
   const deleteLog = async (id) => {
     if (!confirm("Hapus catatan ini?")) return
     try {
-      await fetch(`http://localhost:3000/api/logs/${id}`, { method: 'DELETE' })
+      await api(`/api/logs/${id}`, { method: 'DELETE' })
       fetchLogs()
     } catch (e) { console.error(e) }
   }
@@ -173,6 +239,28 @@ const fetchSettings = async () => {
     </button>
   )
 
+  if (!auth.checked) return null
+
+  // --- LAYAR LOGIN / SETUP AKUN PERTAMA ---
+  if (!auth.authenticated) return (
+    <div className="flex h-screen items-center justify-center bg-slate-950 text-slate-200 font-sans">
+      <form onSubmit={handleLogin} className="w-80 bg-slate-900 border border-slate-800 rounded-2xl p-6 space-y-4 shadow-xl">
+        <div className="flex items-center gap-3">
+          <div className="p-2 bg-gradient-to-br from-indigo-500 to-purple-600 rounded-lg">
+            <Book size={20} className="text-white" />
+          </div>
+          <h1 className="font-bold text-lg text-white">{auth.setup_required ? 'Buat Akun Noty' : 'Login Noty'}</h1>
+        </div>
+        <input type="text" placeholder="Username" value={loginForm.username} onChange={e => setLoginForm({...loginForm, username: e.target.value})} className="w-full bg-slate-950 border border-slate-700 rounded-lg p-2 text-white" />
+        <input type="password" placeholder="Password" value={loginForm.password} onChange={e => setLoginForm({...loginForm, password: e.target.value})} className="w-full bg-slate-950 border border-slate-700 rounded-lg p-2 text-white" />
+        {loginError && <p className="text-red-400 text-sm">{loginError}</p>}
+        <button type="submit" className="w-full bg-indigo-600 hover:bg-indigo-700 text-white p-2 rounded-lg font-semibold">
+          {auth.setup_required ? 'Buat Akun' : 'Masuk'}
+        </button>
+      </form>
+    </div>
+  )
+
   return (
     <div className="flex h-screen bg-slate-950 text-slate-200 font-sans overflow-hidden">
       
@@ -193,6 +281,9 @@ const fetchSettings = async () => {
           <button onClick={() => setView('new-entry')} className="w-full bg-emerald-600 hover:bg-emerald-700 text-white p-3 rounded-xl flex items-center justify-center gap-2 font-semibold shadow-lg">
             <Plus size={20} /> Catatan Baru
           </button>
+          <button onClick={handleLogout} className="w-full mt-2 text-slate-500 hover:text-white text-sm p-2">
+            Logout ({auth.username})
+          </button>
         </div>
       </div>
 
@@ -223,7 +314,7 @@ const fetchSettings = async () => {
                     <div className="prose prose-invert prose-sm max-w-none text-slate-300 mb-4"><ReactMarkdown>{log.content}</ReactMarkdown></div>
                     <div className="flex items-center gap-2 flex-wrap">
                       <span className="px-2 py-1 bg-slate-800 rounded text-[10px] text-indigo-400 uppercase font-bold">{log.category}</span>
-                      {log.tags && JSON.parse(log.tags).map((tag, i) => <span key={i} className="flex items-center gap-1 px-2 py-1 bg-slate-800/50 border border-slate-700 rounded text-[10px] text-slate-400"><Tag size={10} /> {tag}</span>)}
+                      {log.tags?.map((tag, i) => <span key={i} className="flex items-center gap-1 px-2 py-1 bg-slate-800/50 border border-slate-700 rounded text-[10px] text-slate-400"><Tag size={10} /> {tag}</span>)}
                       {log.mood && <span className="ml-auto flex items-center gap-1 text-xs text-slate-500"><Smile size={12} /> {log.mood}</span>}
                     </div>
                   </div>
@@ -280,10 +371,11 @@ const fetchSettings = async () => {
                           if (!newEntry.content) return;
                           setLoading(true);
                           try {
-                            const res = await fetch('http://localhost:3000/api/ai/polish', { method: 'POST', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify({ draft_content: newEntry.content }) });
+                            const res = await api('/api/ai/polish', { method: 'POST', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify({ draft_content: newEntry.content }) });
+                            if (!res.ok) throw new Error(await errorMessage(res));
                             const data = await res.json();
                             setNewEntry(prev => ({ ...prev, content: data.polished_content }));
-                          } catch(e) { alert("AI Error") } finally { setLoading(false); }
+                          } catch(e) { alert(`AI Error: ${e.message}`) } finally { setLoading(false); }
                         }}
                         disabled={loading || !newEntry.content}
                         className="flex items-center gap-1 text-xs bg-indigo-900/50 text-indigo-300 px-3 py-1 rounded-full hover:bg-indigo-600 hover:text-white transition-all disabled:opacity-50"
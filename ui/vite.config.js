@@ -4,4 +4,10 @@ import react from '@vitejs/plugin-react'
 // https://vite.dev/config/
 export default defineConfig({
   plugins: [react()],
+  server: {
+    // Saat development, request /api diteruskan ke server Rust (cookie session tetap same-origin)
+    proxy: {
+      '/api': 'http://localhost:3000',
+    },
+  },
 })